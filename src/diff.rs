@@ -0,0 +1,188 @@
+use crate::path;
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+use std::collections::BTreeMap;
+
+// Compare two configs leaf by leaf and return the sorted dotted paths that were
+// (added, removed, changed). Sequences and empty mappings count as single leaves.
+pub fn config_field_diff(old: &Value, new: &Value) -> (Vec<String>, Vec<String>, Vec<String>) {
+    let mut old_leaves = BTreeMap::new();
+    let mut new_leaves = BTreeMap::new();
+    collect_leaves(old, "", &mut old_leaves);
+    collect_leaves(new, "", &mut new_leaves);
+
+    let added = new_leaves.keys().filter(|field_path| !old_leaves.contains_key(*field_path)).cloned().collect();
+    let removed = old_leaves.keys().filter(|field_path| !new_leaves.contains_key(*field_path)).cloned().collect();
+    let changed = old_leaves
+        .iter()
+        .filter(|(field_path, old_value)| new_leaves.get(*field_path).is_some_and(|new_value| new_value != *old_value))
+        .map(|(field_path, _)| field_path.clone())
+        .collect();
+
+    (added, removed, changed)
+}
+
+fn collect_leaves<'a>(val: &'a Value, prefix: &str, leaves: &mut BTreeMap<String, &'a Value>) {
+    // Walk with an explicit stack so deep configs can't overflow the call stack
+    let mut stack = vec![(prefix.to_string(), val)];
+    while let Some((prefix, val)) = stack.pop() {
+        match val {
+            Value::Mapping(map) if !map.is_empty() => {
+                stack.extend(map.iter().map(|(k, v)| (path::join(&prefix, k), v)));
+            }
+            // A missing or null document has no fields
+            Value::Null if prefix.is_empty() => {}
+            _ => {
+                leaves.insert(prefix, val);
+            }
+        }
+    }
+}
+
+// The parts of `config` that differ from `defaults`: keys the defaults lack and leaves with another value.
+// Leaves are compared like in config_field_diff. Keys only in the defaults can't be expressed and are skipped.
+pub fn overrides(config: &Value, defaults: &Value) -> Value {
+    let Some((map, default_map)) = compare_by_key(config, Some(defaults)) else {
+        return leaf_override(config, Some(defaults)).unwrap_or_else(|| Value::Mapping(Mapping::new()));
+    };
+
+    // Walk with an explicit stack so deep configs can't overflow the call stack. Each frame holds a mapping's
+    // key in its parent, its entries still to compare, the matching defaults and the overrides found so far.
+    let mut stack = vec![(None, map.iter(), default_map, Mapping::new())];
+    while let Some((_, pending, default_map, found)) = stack.last_mut() {
+        let default_map: &Mapping = default_map;
+        if let Some((k, v)) = pending.next() {
+            let default = default_map.get(k);
+            match compare_by_key(v, default) {
+                Some((map, default_map)) => stack.push((Some(k), map.iter(), default_map, Mapping::new())),
+                None => {
+                    if let Some(v) = leaf_override(v, default) {
+                        found.insert(k.clone(), v);
+                    }
+                }
+            }
+            continue;
+        }
+
+        let Some((key, _, _, found)) = stack.pop() else {
+            break;
+        };
+        match (stack.last_mut(), key) {
+            (Some((_, _, _, parent)), Some(key)) => {
+                if !found.is_empty() {
+                    parent.insert(key.clone(), Value::Mapping(found));
+                }
+            }
+            _ => return Value::Mapping(found),
+        }
+    }
+    Value::Mapping(Mapping::new())
+}
+
+// Both sides as mappings when they are compared key by key rather than as a whole
+fn compare_by_key<'a>(config: &'a Value, defaults: Option<&'a Value>) -> Option<(&'a Mapping, &'a Mapping)> {
+    match (config, defaults) {
+        (Value::Mapping(map), Some(Value::Mapping(default_map))) if !map.is_empty() => Some((map, default_map)),
+        _ => None,
+    }
+}
+
+// The override for a value compared as a whole, None when it matches its default
+fn leaf_override(config: &Value, defaults: Option<&Value>) -> Option<Value> {
+    match defaults {
+        None => Some(config.clone()),
+        Some(default) => (config != default).then(|| config.clone()),
+    }
+}
+
+// One RFC 6902 JSON Patch operation, paths are JSON Pointers
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOp {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+}
+
+// A JSON Patch that turns `before` into `after`. Mappings are compared key by key, anything else is
+// replaced as a whole. A removed value that reappears unchanged under a new key becomes a move.
+pub fn json_patch(before: &Value, after: &Value) -> Vec<PatchOp> {
+    let mut changes = PatchChanges::default();
+    collect_patch_changes(before, after, "", &mut changes);
+
+    let mut ops = changes.replaced;
+    for (from, removed) in changes.removed {
+        match changes.added.iter().position(|(_, added)| *added == removed) {
+            Some(index) => {
+                let (path, _) = changes.added.remove(index);
+                ops.push(PatchOp::Move { from, path });
+            }
+            None => ops.push(PatchOp::Remove { path: from }),
+        }
+    }
+    ops.extend(changes.added.into_iter().map(|(path, value)| PatchOp::Add { path, value: value.clone() }));
+    ops
+}
+
+#[derive(Default)]
+struct PatchChanges<'a> {
+    replaced: Vec<PatchOp>,
+    removed: Vec<(String, &'a Value)>,
+    added: Vec<(String, &'a Value)>,
+}
+
+fn collect_patch_changes<'a>(before: &'a Value, after: &'a Value, pointer: &str, changes: &mut PatchChanges<'a>) {
+    // Pairs still to compare and keys found on one side only, popped in document order.
+    // An explicit stack keeps deep configs from overflowing the call stack.
+    enum Step<'a> {
+        Compare(&'a Value, &'a Value, String),
+        Removed(String, &'a Value),
+        Added(String, &'a Value),
+    }
+
+    let mut stack = vec![Step::Compare(before, after, pointer.to_string())];
+    while let Some(step) = stack.pop() {
+        let (before, after, pointer) = match step {
+            Step::Removed(pointer, v) => {
+                changes.removed.push((pointer, v));
+                continue;
+            }
+            Step::Added(pointer, v) => {
+                changes.added.push((pointer, v));
+                continue;
+            }
+            Step::Compare(before, after, pointer) => (before, after, pointer),
+        };
+
+        match (before, after) {
+            (Value::Mapping(old_map), Value::Mapping(new_map)) => {
+                let mut steps = Vec::new();
+                for (k, v) in old_map {
+                    let child = format!("{}/{}", pointer, pointer_token(k));
+                    match new_map.get(k) {
+                        Some(new_value) => steps.push(Step::Compare(v, new_value, child)),
+                        None => steps.push(Step::Removed(child, v)),
+                    }
+                }
+                for (k, v) in new_map {
+                    if !old_map.contains_key(k) {
+                        steps.push(Step::Added(format!("{}/{}", pointer, pointer_token(k)), v));
+                    }
+                }
+                stack.extend(steps.into_iter().rev());
+            }
+            _ if before != after => changes.replaced.push(PatchOp::Replace { path: pointer, value: after.clone() }),
+            _ => {}
+        }
+    }
+}
+
+// A mapping key as a JSON Pointer reference token, with "~" and "/" escaped
+fn pointer_token(key: &Value) -> String {
+    let key = match key {
+        Value::String(s) => s.clone(),
+        other => serde_yaml::to_string(other).unwrap_or_default().trim_end().to_string(),
+    };
+    key.replace('~', "~0").replace('/', "~1")
+}
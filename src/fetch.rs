@@ -0,0 +1,460 @@
+use crate::legacy_migration::{self, MigrationOptions, MigrationSummary};
+use crate::version::SchemaVersion;
+use flate2::read::GzDecoder;
+use log::{info, warn};
+use reqwest::header::{ACCEPT, WWW_AUTHENTICATE};
+use serde_yaml::Value;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs;
+use std::future::Future;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::OnceCell;
+
+const CHART_REPO_URL: &str = "https://raw.githubusercontent.com/redpanda-data/helm-charts";
+
+// How long to wait for the chart files before giving up
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+// How often a failed fetch is retried, waiting 1s, 2s, 4s, ... between attempts
+pub const DEFAULT_RETRIES: u32 = 3;
+pub const DEFAULT_BACKOFF: Duration = Duration::from_secs(1);
+
+// How long a cached chart download is reused before it is fetched again
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+// Source of the target chart's files
+pub trait ChartFetcher {
+    // The chart's default values.yaml
+    fn fetch_values(&self) -> impl Future<Output = Result<String, FetchError>> + Send;
+    // The chart's Chart.yaml (chart version and default app version)
+    fn fetch_chart_metadata(&self) -> impl Future<Output = Result<String, FetchError>> + Send;
+}
+
+// Fetches the chart from GitHub, at a release tag or on main
+pub struct HttpChartFetcher {
+    client: reqwest::Client,
+    git_ref: String,
+}
+
+impl HttpChartFetcher {
+    pub fn new(target_version: Option<SchemaVersion>, timeout: Duration) -> Result<Self, String> {
+        // reqwest picks up HTTPS_PROXY/HTTP_PROXY/NO_PROXY from the environment
+        let client = reqwest::Client::builder().timeout(timeout).build().map_err(|e| e.to_string())?;
+        let git_ref = match target_version {
+            Some(version) => format!("redpanda-{}", version),
+            None => "main".to_string(),
+        };
+        Ok(HttpChartFetcher { client, git_ref })
+    }
+
+    // URL of a file in the redpanda chart
+    pub fn file_url(&self, file_name: &str) -> String {
+        format!("{}/{}/charts/redpanda/{}", CHART_REPO_URL, self.git_ref, file_name)
+    }
+
+    async fn fetch(&self, file_name: &str) -> Result<String, FetchError> {
+        let url = self.file_url(file_name);
+        let response = self.client.get(&url).send().await.and_then(|response| response.error_for_status());
+        let response = response.map_err(|e| request_error(&url, e))?;
+        response.text().await.map_err(|e| FetchError::Network(format!("Failed to read {}: {}", url, e)))
+    }
+}
+
+fn request_error(url: &str, e: reqwest::Error) -> FetchError {
+    let message = format!("Failed to fetch {}: {}", url, e);
+    // The server answered, retrying won't change its mind
+    if e.is_status() {
+        FetchError::Status(message)
+    } else {
+        FetchError::Network(message)
+    }
+}
+
+impl ChartFetcher for HttpChartFetcher {
+    fn fetch_values(&self) -> impl Future<Output = Result<String, FetchError>> + Send {
+        self.fetch("values.yaml")
+    }
+
+    fn fetch_chart_metadata(&self) -> impl Future<Output = Result<String, FetchError>> + Send {
+        self.fetch("Chart.yaml")
+    }
+}
+
+// Media types of an OCI manifest and of the chart archive layer in a Helm OCI artifact
+const OCI_MANIFEST: &str = "application/vnd.oci.image.manifest.v1+json";
+const HELM_CHART_LAYER: &str = "application/vnd.cncf.helm.chart.content.v1.tar+gzip";
+
+// Pulls the chart archive from an OCI registry, e.g. oci://registry-1.docker.io/redpandadata/redpanda:25.1.1.
+// Only anonymous pulls are supported, which is all the public redpanda chart needs.
+pub struct OciChartFetcher {
+    client: reqwest::Client,
+    repository_url: String,
+    tag: String,
+    archive: OnceCell<Vec<u8>>,
+}
+
+impl OciChartFetcher {
+    pub fn new(reference: &str, timeout: Duration) -> Result<Self, String> {
+        let usage = || format!("'{}' is not a chart reference of the form oci://registry/repository:VERSION", reference);
+        let name = reference.strip_prefix("oci://").ok_or_else(usage)?;
+        let (name, tag) = name.rsplit_once(':').filter(|(_, tag)| !tag.is_empty() && !tag.contains('/')).ok_or_else(usage)?;
+        let (registry, repository) = name.split_once('/').filter(|(_, repository)| !repository.is_empty()).ok_or_else(usage)?;
+
+        // Like docker, talk plain HTTP to a registry on the local machine
+        let is_local = ["localhost", "127.0.0.1"].iter().any(|host| registry == *host || registry.starts_with(&format!("{}:", host)));
+        let scheme = if is_local { "http" } else { "https" };
+
+        let client = reqwest::Client::builder().timeout(timeout).build().map_err(|e| e.to_string())?;
+        Ok(OciChartFetcher {
+            client,
+            repository_url: format!("{}://{}/v2/{}", scheme, registry, repository),
+            tag: tag.to_string(),
+            archive: OnceCell::new(),
+        })
+    }
+
+    // GET a registry URL, answering a bearer token challenge with an anonymous token
+    async fn get(&self, url: &str, accept: &str) -> Result<Vec<u8>, FetchError> {
+        let response = self.client.get(url).header(ACCEPT, accept).send().await.map_err(|e| request_error(url, e))?;
+        let response = match bearer_challenge(&response) {
+            Some(token_url) => {
+                let token = self.anonymous_token(&token_url).await?;
+                self.client.get(url).header(ACCEPT, accept).bearer_auth(token).send().await.map_err(|e| request_error(url, e))?
+            }
+            None => response,
+        };
+        let response = response.error_for_status().map_err(|e| request_error(url, e))?;
+        let bytes = response.bytes().await.map_err(|e| FetchError::Network(format!("Failed to read {}: {}", url, e)))?;
+        Ok(bytes.to_vec())
+    }
+
+    async fn anonymous_token(&self, token_url: &str) -> Result<String, FetchError> {
+        let response = self.client.get(token_url).send().await.and_then(|response| response.error_for_status());
+        let token: serde_json::Value = response
+            .map_err(|e| request_error(token_url, e))?
+            .json()
+            .await
+            .map_err(|e| FetchError::Network(format!("Failed to read {}: {}", token_url, e)))?;
+        token
+            .get("token")
+            .or_else(|| token.get("access_token"))
+            .and_then(serde_json::Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| FetchError::Status(format!("{} returned no token", token_url)))
+    }
+
+    // The chart archive, pulled once and shared by values.yaml and Chart.yaml
+    async fn archive(&self) -> Result<&[u8], FetchError> {
+        let archive = self
+            .archive
+            .get_or_try_init(|| async {
+                let manifest_url = format!("{}/manifests/{}", self.repository_url, self.tag);
+                let manifest: serde_json::Value = serde_json::from_slice(&self.get(&manifest_url, OCI_MANIFEST).await?)
+                    .map_err(|e| FetchError::Parse(format!("Invalid manifest at {}: {}", manifest_url, e)))?;
+                let digest = manifest
+                    .get("layers")
+                    .and_then(serde_json::Value::as_array)
+                    .and_then(|layers| layers.iter().find(|layer| layer.get("mediaType").and_then(serde_json::Value::as_str) == Some(HELM_CHART_LAYER)))
+                    .and_then(|layer| layer.get("digest"))
+                    .and_then(serde_json::Value::as_str)
+                    .ok_or_else(|| FetchError::Parse(format!("{} is not a Helm chart", manifest_url)))?;
+                self.get(&format!("{}/blobs/{}", self.repository_url, digest), HELM_CHART_LAYER).await
+            })
+            .await?;
+        Ok(archive)
+    }
+
+    async fn fetch(&self, file_name: &str) -> Result<String, FetchError> {
+        let archive = self.archive().await?;
+        chart_file(archive, file_name)
+    }
+}
+
+impl ChartFetcher for OciChartFetcher {
+    fn fetch_values(&self) -> impl Future<Output = Result<String, FetchError>> + Send {
+        self.fetch("values.yaml")
+    }
+
+    fn fetch_chart_metadata(&self) -> impl Future<Output = Result<String, FetchError>> + Send {
+        self.fetch("Chart.yaml")
+    }
+}
+
+// The token URL of a 401 response's "Bearer realm=...,service=...,scope=..." challenge
+fn bearer_challenge(response: &reqwest::Response) -> Option<String> {
+    if response.status() != reqwest::StatusCode::UNAUTHORIZED {
+        return None;
+    }
+    let challenge = response.headers().get(WWW_AUTHENTICATE)?.to_str().ok()?.strip_prefix("Bearer ")?;
+
+    let mut realm = None;
+    let mut query = Vec::new();
+    for (key, value) in challenge_params(challenge)? {
+        match key {
+            "realm" => realm = Some(value),
+            _ => query.push(format!("{}={}", key, value)),
+        }
+    }
+    let realm = realm?;
+    Some(if query.is_empty() { realm.to_string() } else { format!("{}?{}", realm, query.join("&")) })
+}
+
+// The comma-separated key=value pairs of a challenge; quoted values may contain commas,
+// e.g. scope="repository:charts/redpanda:pull,push"
+fn challenge_params(challenge: &str) -> Option<Vec<(&str, &str)>> {
+    let mut params = Vec::new();
+    let mut rest = challenge.trim();
+    while !rest.is_empty() {
+        let (key, after) = rest.split_once('=')?;
+        let (value, after) = match after.trim_start().strip_prefix('"') {
+            Some(quoted) => quoted.split_once('"')?,
+            None => after.split_once(',').map_or((after.trim(), ""), |(value, after)| (value.trim(), after)),
+        };
+        params.push((key.trim(), value));
+        let after = after.trim_start();
+        rest = after.strip_prefix(',').unwrap_or(after).trim_start();
+    }
+    Some(params)
+}
+
+// Read a file at the root of a gzipped chart archive, e.g. "redpanda/values.yaml"
+pub fn chart_file(archive: &[u8], file_name: &str) -> Result<String, FetchError> {
+    let invalid = |e: std::io::Error| FetchError::Parse(format!("Invalid chart archive: {}", e));
+    let mut entries = tar::Archive::new(GzDecoder::new(archive));
+    for entry in entries.entries().map_err(invalid)? {
+        let mut entry = entry.map_err(invalid)?;
+        let path = entry.path().map_err(invalid)?;
+        let is_root_file = path.components().count() == 2 && path.file_name().is_some_and(|name| name == file_name);
+        if is_root_file {
+            let mut contents = String::new();
+            entry.read_to_string(&mut contents).map_err(invalid)?;
+            return Ok(contents);
+        }
+    }
+    Err(FetchError::Parse(format!("The chart archive has no {}", file_name)))
+}
+
+// Serves fixed chart files, for running the pipeline without a network
+#[derive(Debug, Clone, Default)]
+pub struct StaticFetcher {
+    pub values: String,
+    pub chart_metadata: Option<String>,
+}
+
+impl ChartFetcher for StaticFetcher {
+    fn fetch_values(&self) -> impl Future<Output = Result<String, FetchError>> + Send {
+        let values = self.values.clone();
+        async move { Ok(values) }
+    }
+
+    fn fetch_chart_metadata(&self) -> impl Future<Output = Result<String, FetchError>> + Send {
+        let chart_metadata = self.chart_metadata.clone();
+        async move { chart_metadata.ok_or_else(|| FetchError::Status("No Chart.yaml configured".to_string())) }
+    }
+}
+
+// Snapshot of the chart bundled into the binary, used with --offline
+pub const BUNDLED_VALUES: &str = include_str!("../charts/redpanda/values.yaml");
+pub const BUNDLED_CHART_METADATA: &str = include_str!("../charts/redpanda/Chart.yaml");
+pub const BUNDLED_CHART_VERSION: &str = chart_version(BUNDLED_CHART_METADATA);
+
+// The top-level "version:" of a Chart.yaml, evaluated at compile time for the bundled chart
+const fn chart_version(chart_metadata: &str) -> &str {
+    const KEY: &[u8] = b"\nversion:";
+    let bytes = chart_metadata.as_bytes();
+    let mut start = 0;
+    while start + KEY.len() <= bytes.len() {
+        let mut matched = 0;
+        while matched < KEY.len() && bytes[start + matched] == KEY[matched] {
+            matched += 1;
+        }
+        if matched == KEY.len() {
+            let (_, rest) = bytes.split_at(start + KEY.len());
+            let mut end = 0;
+            while end < rest.len() && rest[end] != b'\n' {
+                end += 1;
+            }
+            let (version, _) = rest.split_at(end);
+            match std::str::from_utf8(version.trim_ascii()) {
+                Ok(version) => return version,
+                Err(_) => panic!("The bundled Chart.yaml version is not UTF-8"),
+            }
+        }
+        start += 1;
+    }
+    panic!("The bundled Chart.yaml has no version")
+}
+
+impl StaticFetcher {
+    // Serves the chart snapshot bundled into the binary
+    pub fn bundled() -> Self {
+        StaticFetcher { values: BUNDLED_VALUES.to_string(), chart_metadata: Some(BUNDLED_CHART_METADATA.to_string()) }
+    }
+}
+
+// Retries another fetcher's network failures with exponential backoff
+pub struct RetryingFetcher<F> {
+    inner: F,
+    retries: u32,
+    backoff: Duration,
+}
+
+impl<F: ChartFetcher + Sync> RetryingFetcher<F> {
+    pub fn new(inner: F, retries: u32, backoff: Duration) -> Self {
+        RetryingFetcher { inner, retries, backoff }
+    }
+
+    async fn retry<Fut>(&self, fetch: impl Fn() -> Fut) -> Result<String, FetchError>
+    where
+        Fut: Future<Output = Result<String, FetchError>>,
+    {
+        let mut backoff = self.backoff;
+        let mut attempt = 0;
+        loop {
+            match fetch().await {
+                Err(FetchError::Network(message)) if attempt < self.retries => {
+                    attempt += 1;
+                    warn!("{}; retrying in {} ({}/{})", message, humantime::format_duration(backoff), attempt, self.retries);
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl<F: ChartFetcher + Sync> ChartFetcher for RetryingFetcher<F> {
+    fn fetch_values(&self) -> impl Future<Output = Result<String, FetchError>> + Send {
+        self.retry(|| self.inner.fetch_values())
+    }
+
+    fn fetch_chart_metadata(&self) -> impl Future<Output = Result<String, FetchError>> + Send {
+        self.retry(|| self.inner.fetch_chart_metadata())
+    }
+}
+
+// Where chart downloads are cached between runs, e.g. ~/.cache/redpanda-chart-upgrade on Linux
+pub fn default_cache_dir() -> PathBuf {
+    dirs::cache_dir().unwrap_or_else(std::env::temp_dir).join(env!("CARGO_PKG_NAME"))
+}
+
+// Caches another fetcher's chart files on disk, so repeated runs against the same chart skip the download
+pub struct CachingFetcher<F> {
+    inner: F,
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl<F: ChartFetcher + Sync> CachingFetcher<F> {
+    // The source, e.g. the chart URL, keys the cache. A zero ttl always fetches but still refreshes the cache.
+    pub fn new(inner: F, cache_dir: &Path, source: &str, ttl: Duration) -> Self {
+        let key: String = Sha256::digest(source.as_bytes()).iter().take(8).map(|byte| format!("{:02x}", byte)).collect();
+        CachingFetcher { inner, dir: cache_dir.join(key), ttl }
+    }
+
+    // The cached copy of a file, unless it is missing or older than the ttl
+    fn read_fresh(&self, file_name: &str) -> Option<String> {
+        let file_path = self.dir.join(file_name);
+        let age = fs::metadata(&file_path).and_then(|metadata| metadata.modified()).ok()?.elapsed().ok()?;
+        if age >= self.ttl {
+            return None;
+        }
+        fs::read_to_string(file_path).ok()
+    }
+
+    // Write through a temporary file so concurrent runs never read a half-written copy
+    fn write(&self, file_name: &str, contents: &str) {
+        let temp_path = self.dir.join(format!("{}.{}.tmp", file_name, std::process::id()));
+        let result = fs::create_dir_all(&self.dir)
+            .and_then(|()| fs::write(&temp_path, contents))
+            .and_then(|()| fs::rename(&temp_path, self.dir.join(file_name)));
+        if let Err(e) = result {
+            warn!("Failed to cache {} in {}: {}", file_name, self.dir.display(), e);
+        }
+    }
+
+    async fn cached<Fut>(&self, file_name: &str, fetch: impl Fn() -> Fut, cacheable: fn(&str) -> bool) -> Result<String, FetchError>
+    where
+        Fut: Future<Output = Result<String, FetchError>>,
+    {
+        if let Some(contents) = self.read_fresh(file_name) {
+            info!("Using the cached {} from {}", file_name, self.dir.display());
+            return Ok(contents);
+        }
+
+        let contents = fetch().await?;
+        // Don't keep e.g. an HTML error page around for the next run
+        if cacheable(&contents) {
+            self.write(file_name, &contents);
+        }
+        Ok(contents)
+    }
+}
+
+impl<F: ChartFetcher + Sync> ChartFetcher for CachingFetcher<F> {
+    fn fetch_values(&self) -> impl Future<Output = Result<String, FetchError>> + Send {
+        self.cached("values.yaml", || self.inner.fetch_values(), |values| parse_chart_values(values).is_ok())
+    }
+
+    fn fetch_chart_metadata(&self) -> impl Future<Output = Result<String, FetchError>> + Send {
+        self.cached("Chart.yaml", || self.inner.fetch_chart_metadata(), |chart| serde_yaml::from_str::<Value>(chart).is_ok())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FetchError {
+    // The chart files couldn't be fetched, e.g. a connection error or timeout
+    Network(String),
+    // The server answered with an error status such as 404
+    Status(String),
+    // The fetched chart values aren't valid YAML
+    Parse(String),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FetchError::Network(message) | FetchError::Status(message) => write!(f, "{}", message),
+            FetchError::Parse(message) => write!(f, "Failed to parse the chart values: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+// Fetch the target chart's values and migrate the input onto them
+pub async fn migrate_onto_chart(
+    fetcher: &impl ChartFetcher,
+    input: Value,
+    options: &MigrationOptions,
+) -> Result<(Value, MigrationSummary), FetchError> {
+    let values = fetcher.fetch_values().await?;
+    let latest = parse_chart_values(&values)?;
+    Ok(legacy_migration::migrate_with_options(input, latest, options))
+}
+
+// Top-level keys every redpanda chart's values.yaml has
+const EXPECTED_CHART_KEYS: [&str; 3] = ["image", "statefulset", "storage"];
+
+// Parse the chart values, making sure they are chart values and not e.g. an HTML error page
+pub fn parse_chart_values(values: &str) -> Result<Value, FetchError> {
+    let values: Value = serde_yaml::from_str(values).map_err(|e| FetchError::Parse(e.to_string()))?;
+    let missing: Vec<&str> = EXPECTED_CHART_KEYS.into_iter().filter(|key| values.get(key).is_none()).collect();
+    if !missing.is_empty() {
+        return Err(FetchError::Parse(format!(
+            "the fetched content doesn't look like redpanda chart values, it has no {}",
+            missing.join(", ")
+        )));
+    }
+    Ok(values)
+}
+
+// Fetch and parse the target chart's Chart.yaml, if it is available
+pub async fn chart_metadata(fetcher: &impl ChartFetcher) -> Option<Value> {
+    let chart = fetcher.fetch_chart_metadata().await.ok()?;
+    serde_yaml::from_str(&chart).ok()
+}
@@ -0,0 +1,125 @@
+use flate2::read::GzDecoder;
+use log::warn;
+use serde::Deserialize;
+use serde_yaml::Value;
+use std::io::Read;
+use std::str::FromStr;
+
+// Magic bytes at the start of every gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+// Decode a values file's contents, decompressing them first when the file is gzipped
+pub fn decode_values(bytes: &[u8], file_name: &str) -> Result<String, String> {
+    if !file_name.ends_with(".gz") && !bytes.starts_with(&GZIP_MAGIC) {
+        return String::from_utf8(bytes.to_vec()).map_err(|e| e.to_string());
+    }
+
+    let mut contents = String::new();
+    GzDecoder::new(bytes).read_to_string(&mut contents).map_err(|e| e.to_string())?;
+    Ok(contents)
+}
+
+// Parse a values file that holds a single document, see parse_documents
+pub fn parse_values(contents: &str, file_name: &str) -> Result<Value, String> {
+    let mut documents = parse_documents(contents, file_name)?;
+    match documents.len() {
+        1 => Ok(documents.remove(0)),
+        count => Err(format!("expected a single YAML document, found {}", count)),
+    }
+}
+
+// Parse every document of a values file, treating it as JSON when it has a .json extension or starts with '{'.
+// YAML files may hold several documents separated by "---". Anchors are expanded in place, the output does not preserve them.
+pub fn parse_documents(contents: &str, file_name: &str) -> Result<Vec<Value>, String> {
+    let file_name = file_name.strip_suffix(".gz").unwrap_or(file_name);
+    if file_name.ends_with(".json") || contents.trim_start().starts_with('{') {
+        return serde_json::from_str(contents).map(|val| vec![val]).map_err(|e| e.to_string());
+    }
+
+    // Aliases are resolved while parsing; expand "<<" merge keys too so every node is concrete
+    let mut documents: Vec<Value> = if contents.contains("---") {
+        serde_yaml::Deserializer::from_str(contents).map(Value::deserialize).collect::<Result<_, _>>().map_err(|e| e.to_string())?
+    } else {
+        vec![serde_yaml::from_str(contents).map_err(|e| e.to_string())?]
+    };
+    if documents.is_empty() {
+        documents.push(Value::Null);
+    }
+    for val in &mut documents {
+        val.apply_merge().map_err(|e| e.to_string())?;
+    }
+    Ok(documents)
+}
+
+// A Helm-style "--set dotted.path=value" override
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetOverride {
+    pub path: String,
+    pub value: Value,
+    // Whether the value was a YAML null ("null" or "~"), which is set as the string unless nulls are preserved
+    pub null: bool,
+}
+
+impl FromStr for SetOverride {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((path, raw)) = s.split_once('=') else {
+            return Err(format!("'{}' is not in the form path=value", s));
+        };
+        if path.is_empty() || path.split('.').any(str::is_empty) {
+            return Err(format!("'{}' is not a valid dotted path", path));
+        }
+
+        // Read the value as a YAML scalar so "3" is a number and "true" a bool, anything else stays a string
+        let parsed = serde_yaml::from_str::<Value>(raw);
+        let null = !raw.is_empty() && matches!(parsed, Ok(Value::Null));
+        let value = match parsed {
+            Ok(value @ (Value::Bool(_) | Value::Number(_) | Value::String(_))) => value,
+            _ => Value::String(raw.to_string()),
+        };
+        Ok(SetOverride { path: path.to_string(), value, null })
+    }
+}
+
+// Substitute "${VAR}" placeholders using the given lookup, leaving unknown variables untouched
+pub fn expand_env_vars(text: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut expanded = String::with_capacity(text.len());
+    let mut missing: Vec<&str> = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+
+        let name_len = after.find('}').filter(|&end| is_env_var_name(&after[..end]));
+        match name_len {
+            Some(end) => {
+                let name = &after[..end];
+                match lookup(name) {
+                    Some(value) => expanded.push_str(&value),
+                    None => {
+                        if !missing.contains(&name) {
+                            warn!("Environment variable {} is not set; leaving ${{{}}} untouched", name, name);
+                            missing.push(name);
+                        }
+                        expanded.push_str(&rest[start..start + end + 3]);
+                    }
+                }
+                rest = &after[end + 1..];
+            }
+            None => {
+                expanded.push_str("${");
+                rest = after;
+            }
+        }
+    }
+
+    expanded.push_str(rest);
+    expanded
+}
+
+fn is_env_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_') && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
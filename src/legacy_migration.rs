@@ -0,0 +1,817 @@
+use crate::path;
+use crate::quantity;
+use crate::validation::BOOLEAN_FIELDS;
+use log::{info, warn};
+use serde_yaml::Value;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Default)]
+pub struct MigrationOptions {
+    // Print the differences between the input and latest configs to stdout
+    pub print_diffs: bool,
+    // Keep empty mappings and sequences instead of pruning them from the result
+    pub keep_empty: bool,
+    // Only migrate the input, without layering in the latest chart's defaults
+    pub skip_merge: bool,
+    // Dotted paths of the latest chart's subtrees to leave out of the merge
+    pub merge_exclude: Vec<String>,
+    // When set, only these dotted paths of the latest chart are merged in
+    pub merge_include_only: Vec<String>,
+    // How the latest chart's values are merged into the input
+    pub merge_strategy: MergeStrategy,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergeStrategy {
+    // Add every missing key, descending into mappings both sides have
+    #[default]
+    KeepExisting,
+    // Only add top-level keys the input lacks entirely, leaving its existing subtrees as they are
+    AddNewOnly,
+}
+
+impl FromStr for MergeStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "keep-existing" => Ok(MergeStrategy::KeepExisting),
+            "add-new-only" => Ok(MergeStrategy::AddNewOnly),
+            other => Err(format!("Unknown merge strategy '{}', expected keep-existing or add-new-only", other)),
+        }
+    }
+}
+
+// Counts of the changes a migration made; a moved key counts once, as migrated
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationSummary {
+    pub added: usize,
+    pub removed: usize,
+    pub migrated: usize,
+    // Every removed key, with the reason it was removed
+    pub removed_fields: Vec<RemovedField>,
+}
+
+impl MigrationSummary {
+    fn record_removal(&mut self, field_path: impl Into<String>, reason: impl Into<String>) {
+        self.removed += 1;
+        self.removed_fields.push(RemovedField { field_path: field_path.into(), reason: reason.into() });
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemovedField {
+    pub field_path: String,
+    pub reason: String,
+}
+
+// Migrate an existing deployment's values onto the latest chart values
+pub fn migrate(input: Value, latest: Value) -> Value {
+    migrate_with_options(input, latest, &MigrationOptions::default()).0
+}
+
+pub fn migrate_with_options(mut input: Value, latest: Value, options: &MigrationOptions) -> (Value, MigrationSummary) {
+    let mut summary = MigrationSummary::default();
+
+    // Rename the specified keys in the input
+    rename_nested_keys(&mut input, &mut summary);
+    normalize_boolean_strings(&mut input, &mut summary);
+    normalize_storage_classes(&mut input, &mut summary);
+    migrate_monitoring(&mut input, &mut summary);
+    migrate_external_access(&mut input, &mut summary);
+    migrate_sasl_bootstrap_user(&mut input, &mut summary);
+    migrate_listener_authentication(&mut input, &mut summary);
+    migrate_console(&mut input, &latest, &mut summary);
+    migrate_tuning(&mut input, &mut summary);
+    migrate_sidecar_controllers(&mut input, &mut summary);
+    normalize_resource_units(&mut input, &mut summary);
+
+    // Print the differences between the two YAML files
+    if options.print_diffs {
+        info!("Differences between the two files:");
+        print_diffs(&input, &latest);
+    }
+
+    // Merge the latest YAML into the input, keeping the input's values
+    if !options.skip_merge {
+        let mut latest = filter_merge_paths(latest, &options.merge_include_only, &options.merge_exclude);
+        // Prune the chart's empty defaults up front, so they are neither counted as added nor as removed
+        if !options.keep_empty {
+            prune_empty(&mut latest);
+        }
+        match options.merge_strategy {
+            MergeStrategy::KeepExisting => {
+                summary.added += count_new_keys(&input, &latest);
+                merge(&mut input, &latest);
+            }
+            MergeStrategy::AddNewOnly => summary.added += add_new_keys(&mut input, latest),
+        }
+    }
+
+    // Keep version-like fields as strings so they aren't written back as floats
+    quote_string_fields(&mut input);
+
+    // Drop the empty blocks left behind by the migrations, all of them from the input
+    if !options.keep_empty {
+        for field_path in prune_empty(&mut input) {
+            summary.record_removal(field_path, "empty after migration");
+        }
+    }
+
+    (input, summary)
+}
+
+// Print differences between two YAML values
+fn print_diffs(val1: &Value, val2: &Value) {
+    // Lines still to print and value pairs still to compare, popped in printing order
+    enum Step<'a> {
+        Compare(&'a Value, &'a Value, usize),
+        Print(String),
+    }
+
+    let mut stack = vec![Step::Compare(val1, val2, 0)];
+    while let Some(step) = stack.pop() {
+        let (val1, val2, indent) = match step {
+            Step::Print(line) => {
+                info!("{}", line);
+                continue;
+            }
+            Step::Compare(val1, val2, indent) => (val1, val2, indent),
+        };
+        let (Value::Mapping(map1), Value::Mapping(map2)) = (val1, val2) else {
+            if val1 != val2 {
+                info!("{}Key has different values. existing: '{:?}' vs latest: '{:?}'.", " ".repeat(indent), val1, val2);
+            }
+            continue;
+        };
+
+        let mut steps = Vec::new();
+        for (k, v1) in map1 {
+            match map2.get(k) {
+                Some(v2) => steps.push(Step::Compare(v1, v2, indent + 2)),
+                None => steps.push(Step::Print(format!(
+                    "{}Key '{}' is only in the existing deployment config.",
+                    " ".repeat(indent),
+                    k.as_str().unwrap_or("<unknown key>")
+                ))),
+            }
+        }
+        for k in map2.keys() {
+            if !map1.contains_key(k) {
+                steps.push(Step::Print(format!("{}Key '{}' is only in the latest config.", " ".repeat(indent), k.as_str().unwrap_or("<unknown key>"))));
+            }
+        }
+        stack.extend(steps.into_iter().rev());
+    }
+}
+
+// Deep-merge values files left to right, so later files override earlier ones
+pub fn merge_layers(layers: impl IntoIterator<Item = Value>) -> Value {
+    layers.into_iter().fold(Value::Null, |combined, mut layer| {
+        // An empty file contributes nothing
+        if layer.is_null() {
+            return combined;
+        }
+        merge(&mut layer, &combined);
+        layer
+    })
+}
+
+// Count the keys that merging val2 into val1 would add
+fn count_new_keys(val1: &Value, val2: &Value) -> usize {
+    let mut count = 0;
+    let mut stack = vec![(val1, val2)];
+    while let Some((val1, val2)) = stack.pop() {
+        let (Value::Mapping(map1), Value::Mapping(map2)) = (val1, val2) else {
+            continue;
+        };
+        for (k, v2) in map2 {
+            match map1.get(k) {
+                Some(v1) => stack.push((v1, v2)),
+                None => count += 1,
+            }
+        }
+    }
+    count
+}
+
+// Add the top-level keys of val2 that val1 lacks, returning how many were added
+fn add_new_keys(val1: &mut Value, val2: Value) -> usize {
+    let (Value::Mapping(map1), Value::Mapping(map2)) = (val1, val2) else {
+        return 0;
+    };
+
+    let mut added = 0;
+    for (k, v2) in map2 {
+        if !map1.contains_key(&k) {
+            map1.insert(k, v2);
+            added += 1;
+        }
+    }
+    added
+}
+
+// Limit the latest chart values to the subtrees that should be merged in
+pub fn filter_merge_paths(latest: Value, include_only: &[String], exclude: &[String]) -> Value {
+    let mut filtered = if include_only.is_empty() {
+        latest
+    } else {
+        let mut included = Value::Mapping(serde_yaml::Mapping::new());
+        for field_path in include_only {
+            if let Some(v) = path::get(&latest, field_path) {
+                path::set(&mut included, field_path, v.clone());
+            }
+        }
+        included
+    };
+
+    for field_path in exclude {
+        path::remove(&mut filtered, field_path);
+    }
+    filtered
+}
+
+// Merge YAML values, keeping the first file's values
+pub fn merge(val1: &mut Value, val2: &Value) {
+    // Walk nested mappings with an explicit stack so deep configs can't overflow the call stack
+    let mut stack = vec![(val1, val2)];
+    while let Some((val1, val2)) = stack.pop() {
+        if let (Value::Mapping(map1), Value::Mapping(map2)) = (val1, val2) {
+            for (k, v2) in map2 {
+                if !map1.contains_key(k) {
+                    map1.insert(k.clone(), v2.clone());
+                }
+            }
+
+            // Merge nested mappings present on both sides
+            for (k, v1) in map1.iter_mut() {
+                if let Some(v2) = map2.get(k) {
+                    if v1.is_mapping() && v2.is_mapping() {
+                        stack.push((v1, v2));
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn rename_nested_keys(val: &mut Value, summary: &mut MigrationSummary) {
+    // Nested mappings are renamed before the mappings that hold them
+    for_each_mapping_bottom_up(val, |map| rename_keys(map, summary));
+}
+
+// Call f on val and every mapping nested in it through mappings, children before their parents. The mappings
+// are taken apart and rebuilt in order on an explicit stack, so deep configs can't overflow the call stack.
+fn for_each_mapping_bottom_up(val: &mut Value, mut f: impl FnMut(&mut serde_yaml::Mapping)) {
+    let Value::Mapping(root) = std::mem::take(val) else {
+        return;
+    };
+
+    // Each frame holds a mapping's key in its parent, its entries still to visit and the ones visited
+    let mut stack = vec![(None, root.into_iter(), serde_yaml::Mapping::new())];
+    while let Some((_, pending, visited)) = stack.last_mut() {
+        if let Some((k, v)) = pending.next() {
+            match v {
+                Value::Mapping(child) => stack.push((Some(k), child.into_iter(), serde_yaml::Mapping::new())),
+                v => {
+                    visited.insert(k, v);
+                }
+            }
+            continue;
+        }
+
+        let Some((key, _, mut map)) = stack.pop() else {
+            break;
+        };
+        f(&mut map);
+        match (stack.last_mut(), key) {
+            (Some((_, _, parent)), Some(key)) => {
+                parent.insert(key, Value::Mapping(map));
+            }
+            _ => *val = Value::Mapping(map),
+        }
+    }
+}
+
+// Apply the old key renames to the keys of a single mapping
+fn rename_keys(map: &mut serde_yaml::Mapping, summary: &mut MigrationSummary) {
+    // Move keys from "storage.tieredConfig.*" to "storage.tiered.config.*"
+    if let Some(Value::Mapping(tiered_config_map)) = map.remove(Value::String("tieredConfig".to_string())) {
+        info!("✓ Migrating storage.tieredConfig -> storage.tiered.config");
+        summary.migrated += 1;
+        if let Some(Value::Mapping(tiered_map)) = map.get_mut(Value::String("tiered".to_string())) {
+            let config_entry = tiered_map
+                .entry(Value::String("config".to_string()))
+                .or_insert_with(|| Value::Mapping(serde_yaml::Mapping::new()));
+
+            if let Value::Mapping(ref mut config_map) = config_entry {
+                for (k, v) in tiered_config_map {
+                    config_map.insert(k, v);
+                }
+            }
+        } else {
+            let mut new_tiered_map = serde_yaml::Mapping::new();
+            let mut new_config_map = serde_yaml::Mapping::new();
+            for (k, v) in tiered_config_map {
+                new_config_map.insert(k, v);
+            }
+            new_tiered_map.insert(Value::String("config".to_string()), Value::Mapping(new_config_map));
+            map.insert(Value::String("tiered".to_string()), Value::Mapping(new_tiered_map));
+        }
+    }
+
+    // Rename "storage.tieredStorageHostPath" -> "storage.tiered.hostPath"
+    if let Some(tiered_storage_host_path) = map.remove(Value::String("tieredStorageHostPath".to_string())) {
+        if let Some(Value::Mapping(tiered_map)) = map.get_mut(Value::String("tiered".to_string())) {
+            info!("✓ Migrating storage.tieredStorageHostPath -> storage.tiered.hostPath");
+            summary.migrated += 1;
+            tiered_map.insert(Value::String("hostPath".to_string()), tiered_storage_host_path);
+        } else {
+            warn!("Dropping storage.tieredStorageHostPath: there is no storage.tiered block to move it into");
+            summary.record_removal("storage.tieredStorageHostPath", "there is no storage.tiered block to move it into");
+        }
+    }
+
+    // Rename "storage.tieredStoragePersistentVolume" -> "storage.tiered.persistentVolume"
+    if let Some(tiered_storage_pv) = map.remove(Value::String("tieredStoragePersistentVolume".to_string())) {
+        if let Some(Value::Mapping(tiered_map)) = map.get_mut(Value::String("tiered".to_string())) {
+            info!("✓ Migrating storage.tieredStoragePersistentVolume -> storage.tiered.persistentVolume");
+            summary.migrated += 1;
+            tiered_map.insert(Value::String("persistentVolume".to_string()), tiered_storage_pv);
+        } else {
+            warn!("Dropping storage.tieredStoragePersistentVolume: there is no storage.tiered block to move it into");
+            summary.record_removal("storage.tieredStoragePersistentVolume", "there is no storage.tiered block to move it into");
+        }
+    }
+
+    // Move and rename keys inside "license_secret_ref" -> "enterprise.licenseSecretRef"
+    if let Some(Value::Mapping(mut license_secret_ref_map)) = map.remove(Value::String("license_secret_ref".to_string())) {
+        info!("✓ Migrating license_secret_ref -> enterprise.licenseSecretRef");
+        summary.migrated += 1;
+        // Rename "secret_name" -> "name" and "secret_key" -> "key" inside the object
+        if let Some(secret_name) = license_secret_ref_map.remove(Value::String("secret_name".to_string())) {
+            license_secret_ref_map.insert(Value::String("name".to_string()), secret_name);
+        }
+        if let Some(secret_key) = license_secret_ref_map.remove(Value::String("secret_key".to_string())) {
+            license_secret_ref_map.insert(Value::String("key".to_string()), secret_key);
+        }
+
+        // Move to "enterprise.licenseSecretRef"
+        let enterprise_entry = map
+            .entry(Value::String("enterprise".to_string()))
+            .or_insert_with(|| Value::Mapping(serde_yaml::Mapping::new()));
+
+        if let Value::Mapping(enterprise_map) = enterprise_entry {
+            enterprise_map.insert(Value::String("licenseSecretRef".to_string()), Value::Mapping(license_secret_ref_map));
+        }
+    }
+
+    // Rename "license_key" -> "enterprise.license"
+    if let Some(license_key) = map.remove(Value::String("license_key".to_string())) {
+        info!("✓ Migrating license_key -> enterprise.license");
+        summary.migrated += 1;
+        let enterprise_entry = map
+            .entry(Value::String("enterprise".to_string()))
+            .or_insert_with(|| Value::Mapping(serde_yaml::Mapping::new()));
+
+        if let Value::Mapping(enterprise_map) = enterprise_entry {
+            enterprise_map.insert(Value::String("license".to_string()), license_key);
+        }
+    }
+}
+
+// The chart still reads "monitoring.*" in place, so an enabled block is kept as-is.
+// A disabled block keeps "enabled: false" and drops its stale settings.
+pub fn migrate_monitoring(val: &mut Value, summary: &mut MigrationSummary) {
+    let Some(Value::Mapping(monitoring_map)) = path::get_mut(val, "monitoring") else {
+        return;
+    };
+    if monitoring_map.get("enabled").and_then(Value::as_bool) != Some(false) {
+        return;
+    }
+
+    let stale: Vec<Value> = monitoring_map.keys().filter(|k| k.as_str() != Some("enabled")).cloned().collect();
+    for k in stale {
+        monitoring_map.shift_remove(&k);
+        info!("✓ Dropping monitoring.{} of disabled monitoring", k.as_str().unwrap_or("<unknown key>"));
+        summary.record_removal(path::join("monitoring", &k), "monitoring is disabled");
+    }
+}
+
+// Move the old "console.config" block under "console.console", when the latest chart has that nested block.
+// Charts that read "console.config" directly (e.g. 25.1) keep it in place. "console.ingress" is read
+// from the top-level console block in both layouts and is never moved.
+pub fn migrate_console(val: &mut Value, latest: &Value, summary: &mut MigrationSummary) {
+    let Some(Value::Mapping(console_map)) = path::get_mut(val, "console") else {
+        return;
+    };
+
+    // Console is disabled, its old config is stale
+    if console_map.get("enabled").and_then(Value::as_bool) == Some(false) {
+        if console_map.remove("config").is_some() {
+            info!("✓ Dropping console.config of disabled console");
+            summary.record_removal("console.config", "console is disabled");
+        }
+        return;
+    }
+
+    if !path::get(latest, "console.console").is_some_and(Value::is_mapping) {
+        return;
+    }
+    let Some(Value::Mapping(old_map)) = console_map.remove("config") else {
+        return;
+    };
+    info!("✓ Migrating console.config -> console.console.config");
+    summary.migrated += 1;
+
+    let nested_entry = console_map
+        .entry(Value::String("console".to_string()))
+        .or_insert_with(|| Value::Mapping(serde_yaml::Mapping::new()));
+    let Value::Mapping(nested_map) = nested_entry else {
+        return;
+    };
+    let new_entry = nested_map
+        .entry(Value::String("config".to_string()))
+        .or_insert_with(|| Value::Mapping(serde_yaml::Mapping::new()));
+
+    if let Value::Mapping(new_map) = new_entry {
+        // Keys already in the new layout win over the old ones
+        for (k, v) in old_map {
+            new_map.entry(k).or_insert(v);
+        }
+    }
+}
+
+// Carry the old tuning init container's intent over to the top-level "tuning" block, which holds rpk tuner flags.
+// Only an explicit "enabled" says anything about the tuners, 5.0.10 values never set it and are left alone.
+pub fn migrate_tuning(val: &mut Value, summary: &mut MigrationSummary) {
+    let enabled = match path::get(val, "statefulset.initContainers.tuning.enabled").and_then(Value::as_bool) {
+        Some(enabled) => enabled,
+        None => return,
+    };
+    let Some(Value::Mapping(mut tuning_map)) = path::remove(val, "statefulset.initContainers.tuning") else {
+        return;
+    };
+    tuning_map.shift_remove("enabled");
+
+    // Tuning was switched off, there's nothing to carry over
+    if !enabled {
+        info!("✓ Dropping disabled statefulset.initContainers.tuning");
+        summary.record_removal("statefulset.initContainers.tuning", "tuning was disabled");
+        return;
+    }
+
+    if path::get(val, "tuning.tune_aio_events").is_none() {
+        info!("✓ Migrating statefulset.initContainers.tuning.enabled -> tuning.tune_aio_events");
+        summary.migrated += 1;
+        path::set(val, "tuning.tune_aio_events", Value::Bool(true));
+    }
+
+    // The init container's own settings (resources, extraVolumeMounts) aren't tuner flags and have no home
+    for (k, v) in tuning_map {
+        if is_unset(&v) {
+            continue;
+        }
+        let field_path = path::join("statefulset.initContainers.tuning", &k);
+        warn!("Dropping {}: the chart no longer runs a tuning init container", field_path);
+        summary.record_removal(field_path, "the chart no longer runs a tuning init container");
+    }
+}
+
+fn is_unset(val: &Value) -> bool {
+    match val {
+        Value::Null => true,
+        Value::String(s) => s.is_empty(),
+        Value::Mapping(m) => m.is_empty(),
+        Value::Sequence(seq) => seq.is_empty(),
+        _ => false,
+    }
+}
+
+// Rewrite boolean-like strings ("true", "no", ...) at known boolean fields as real booleans,
+// so the "enabled" checks in the later steps see them
+pub fn normalize_boolean_strings(val: &mut Value, summary: &mut MigrationSummary) {
+    for field_path in BOOLEAN_FIELDS {
+        let Some(field) = path::get_mut(val, field_path) else {
+            continue;
+        };
+        let Value::String(s) = field else {
+            continue;
+        };
+        let flag = match s.trim().to_ascii_lowercase().as_str() {
+            "true" | "yes" => true,
+            "false" | "no" => false,
+            _ => continue,
+        };
+        info!("✓ Converting {} {:?} to {}", field_path, s, flag);
+        summary.migrated += 1;
+        *field = Value::Bool(flag);
+    }
+}
+
+// Storage class fields, where "" (the chart default), null and omitted all mean "use the cluster's default class"
+const STORAGE_CLASS_FIELDS: [&str; 2] = ["storage.persistentVolume.storageClass", "storage.tiered.persistentVolume.storageClass"];
+
+// Rewrite a null storage class as "", the chart's canonical form for the cluster default,
+// so the merge keeps one spelling. "-" (no storage class at all) is left as-is.
+pub fn normalize_storage_classes(val: &mut Value, summary: &mut MigrationSummary) {
+    for field_path in STORAGE_CLASS_FIELDS {
+        if let Some(field) = path::get_mut(val, field_path) {
+            if field.is_null() {
+                info!("✓ Normalizing {} null -> \"\"", field_path);
+                summary.migrated += 1;
+                *field = Value::String(String::new());
+            }
+        }
+    }
+}
+
+// The controllers of the old "sideCars.controllers.run" list and the sidecars that replaced them
+const SIDECAR_CONTROLLERS: [(&str, &str); 2] = [("decommission", "brokerDecommissioner"), ("nodeWatcher", "pvcUnbinder")];
+
+// Replace the old all-in-one statefulset.sideCars.controllers sidecar with the per-controller
+// sidecars of the new chart. Its other settings have no home there and are dropped.
+pub fn migrate_sidecar_controllers(val: &mut Value, summary: &mut MigrationSummary) {
+    let Some(Value::Mapping(mut controllers)) = path::remove(val, "statefulset.sideCars.controllers") else {
+        return;
+    };
+
+    if controllers.shift_remove("enabled").and_then(|enabled| enabled.as_bool()) != Some(true) {
+        info!("✓ Dropping disabled statefulset.sideCars.controllers");
+        summary.record_removal("statefulset.sideCars.controllers", "the controllers sidecar was disabled");
+        return;
+    }
+
+    // The old chart ran every controller unless told otherwise
+    let run: Vec<String> = match controllers.shift_remove("run") {
+        Some(Value::Sequence(run)) => run.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+        _ => vec!["all".to_string()],
+    };
+    for controller in run.iter().filter(|controller| *controller != "all" && !SIDECAR_CONTROLLERS.iter().any(|(name, _)| name == controller)) {
+        warn!("statefulset.sideCars.controllers.run lists unknown controller {:?}; it is not carried over", controller);
+    }
+
+    for (controller, sidecar) in SIDECAR_CONTROLLERS {
+        let enabled_path = format!("statefulset.sideCars.{}.enabled", sidecar);
+        if run.iter().any(|name| name == "all" || name == controller) && path::get(val, &enabled_path).is_none() {
+            info!("✓ Migrating statefulset.sideCars.controllers ({}) -> {}", controller, enabled_path);
+            summary.migrated += 1;
+            path::set(val, &enabled_path, Value::Bool(true));
+        }
+    }
+
+    for (k, _) in controllers {
+        let reason = match k.as_str() {
+            Some("image") => "the sidecars run the image in statefulset.sideCars.image",
+            _ => "not configurable on the sidecars that replaced the controllers sidecar",
+        };
+        summary.record_removal(path::join("statefulset.sideCars.controllers", &k), reason);
+    }
+}
+
+// Rewrite a resource's request and limit in the same canonical unit, so "1" and "1000m" read alike.
+// CPU becomes millicpu and memory the largest binary unit both fit in. Unparseable pairs are left alone.
+pub fn normalize_resource_units(val: &mut Value, summary: &mut MigrationSummary) {
+    for resource in ["cpu", "memory"] {
+        let request_path = format!("resources.requests.{}", resource);
+        let limit_path = format!("resources.limits.{}", resource);
+        let (Some(request), Some(limit)) = (path::get(val, &request_path), path::get(val, &limit_path)) else {
+            continue;
+        };
+
+        let canonical = match resource {
+            "cpu" => quantity::canonical_cpu(request).zip(quantity::canonical_cpu(limit)),
+            _ => quantity::canonical_memory(&[request, limit]).map(|mut forms| (forms.remove(0), forms.remove(0))),
+        };
+        let Some((request, limit)) = canonical else {
+            continue;
+        };
+
+        for (field_path, canonical) in [(request_path, request), (limit_path, limit)] {
+            let field = path::get_mut(val, &field_path).expect("checked above");
+            if field.as_str() != Some(canonical.as_str()) {
+                info!("✓ Normalizing {} to {}", field_path, canonical);
+                summary.migrated += 1;
+                *field = Value::String(canonical);
+            }
+        }
+    }
+}
+
+// Fields the chart expects as strings, even when YAML would read them as numbers (e.g. "tag: 25.2")
+const STRING_FIELDS: [&str; 3] = ["image.tag", "console.image.tag", "connectors.image.tag"];
+
+// Convert numeric values of known string fields to strings, so they serialize quoted.
+// Returns the fields that were read as floats: their text may have lost trailing zeros (1.10 -> 1.1).
+pub fn quote_string_fields(val: &mut Value) -> Vec<String> {
+    let mut lossy = Vec::new();
+    for field_path in STRING_FIELDS {
+        if let Some(field) = path::get_mut(val, field_path) {
+            if let Value::Number(n) = field {
+                info!("✓ Quoting {} ({}) as a string", field_path, n);
+                if n.is_f64() {
+                    warn!("{} was read as the number {}; if it was written with trailing zeros (e.g. {}0), quote it in the input to keep them", field_path, n, n);
+                    lossy.push(field_path.to_string());
+                }
+                *field = Value::String(n.to_string());
+            }
+        }
+    }
+    lossy
+}
+
+// Move the old top-level "external.advertisedPorts", which only applied to the Kafka listener, onto each of
+// the Kafka listener's external entries. "enabled", "type" and "domain" are still read at the top level and stay there.
+pub fn migrate_external_access(val: &mut Value, summary: &mut MigrationSummary) {
+    let Some(Value::Mapping(external_map)) = val.get_mut("external") else {
+        return;
+    };
+    let Some(advertised_ports) = external_map.shift_remove("advertisedPorts") else {
+        return;
+    };
+
+    info!("✓ Migrating external.advertisedPorts -> listeners.kafka.external.*.advertisedPorts");
+    summary.migrated += 1;
+    let Some(Value::Mapping(externals)) = path::get_mut(val, "listeners.kafka.external").filter(|externals| externals.as_mapping().is_some_and(|map| !map.is_empty())) else {
+        path::set(val, "listeners.kafka.external.default.advertisedPorts", advertised_ports);
+        return;
+    };
+    // Ports already set on an external entry win over the old shared ones
+    for (_, external) in externals.iter_mut() {
+        if let Value::Mapping(external_map) = external {
+            external_map.entry(Value::String("advertisedPorts".to_string())).or_insert_with(|| advertised_ports.clone());
+        }
+    }
+}
+
+// Move the first user of the old inline "auth.sasl.users" list into "auth.sasl.bootstrapUser"
+pub fn migrate_sasl_bootstrap_user(val: &mut Value, summary: &mut MigrationSummary) {
+    let Some(Value::Mapping(sasl_map)) = val.get_mut("auth").and_then(|auth| auth.get_mut("sasl")) else {
+        return;
+    };
+    let Some(Value::Sequence(users)) = sasl_map.get("users") else {
+        return;
+    };
+    let Some(first_user) = users.first() else {
+        return;
+    };
+
+    // Both layouts are present, the new one wins
+    if sasl_map.contains_key("bootstrapUser") {
+        warn!("auth.sasl has both a users list and a bootstrapUser; keeping the existing bootstrapUser");
+        return;
+    }
+    let Value::Mapping(first_user) = first_user else {
+        warn!("auth.sasl.users[0] is not a mapping; leaving auth.sasl.users untouched");
+        return;
+    };
+
+    info!("✓ Migrating auth.sasl.users[0] -> auth.sasl.bootstrapUser");
+    summary.migrated += 1;
+    let mut bootstrap_user = first_user.clone();
+    if let Some(Value::Sequence(users)) = sasl_map.get_mut("users") {
+        users.remove(0);
+        if users.is_empty() {
+            sasl_map.remove("users");
+        }
+    }
+
+    // The user inherits the mechanism that used to be set for the whole listener
+    if !bootstrap_user.contains_key("mechanism") {
+        if let Some(mechanism) = sasl_map.remove("mechanism") {
+            bootstrap_user.insert(Value::String("mechanism".to_string()), mechanism);
+        }
+    }
+
+    sasl_map.insert(Value::String("bootstrapUser".to_string()), Value::Mapping(bootstrap_user));
+}
+
+// Listeners that take an authenticationMethod, and the method SASL maps to on each
+const SASL_LISTENER_METHODS: [(&str, &str); 3] = [("kafka", "sasl"), ("http", "http_basic"), ("schemaRegistry", "http_basic")];
+
+// Turn the global "auth.sasl.enabled" into an authenticationMethod on each listener that doesn't set one
+pub fn migrate_listener_authentication(val: &mut Value, summary: &mut MigrationSummary) {
+    if path::get(val, "auth.sasl.enabled").and_then(Value::as_bool) != Some(true) {
+        return;
+    }
+
+    let mut migrated = false;
+    for (name, method) in SASL_LISTENER_METHODS {
+        let listener_path = format!("listeners.{}", name);
+        let listener = path::get(val, &listener_path);
+        if listener.and_then(|listener| listener.get("enabled")).and_then(Value::as_bool) == Some(false) {
+            continue;
+        }
+
+        // The listener itself and each of its external listeners
+        let mut target_paths = vec![listener_path.clone()];
+        if let Some(Value::Mapping(externals)) = listener.and_then(|listener| listener.get("external")) {
+            target_paths.extend(
+                externals
+                    .iter()
+                    .filter(|(_, external)| external.get("enabled").and_then(Value::as_bool) != Some(false))
+                    .map(|(external_name, _)| path::join(&format!("{}.external", listener_path), external_name)),
+            );
+        }
+
+        for target_path in target_paths {
+            let method_path = format!("{}.authenticationMethod", target_path);
+            // An explicit method on the listener wins
+            if path::get(val, &method_path).is_some_and(|existing| !existing.is_null()) {
+                continue;
+            }
+            path::set(val, &method_path, Value::String(method.to_string()));
+            migrated = true;
+        }
+    }
+
+    if migrated {
+        info!("✓ Migrating auth.sasl.enabled -> listeners.*.authenticationMethod");
+        summary.migrated += 1;
+    }
+}
+
+// Remove keys whose value is an empty mapping or sequence, bottom-up.
+// Returns the dotted paths of the removed keys.
+pub fn prune_empty(val: &mut Value) -> Vec<String> {
+    let mut removed = Vec::new();
+    if !val.is_mapping() && !val.is_sequence() {
+        return removed;
+    }
+
+    // Walk with an explicit stack so deep configs can't overflow the call stack. A collection is
+    // only finished once all its children are, so nested empties are pruned before their parents.
+    let mut stack = vec![PruneFrame::new(None, String::new(), std::mem::take(val))];
+    while let Some(frame) = stack.last_mut() {
+        if let Some((key, child)) = frame.pending.next() {
+            let field_path = match &key {
+                Some(key) => path::join(&frame.field_path, key),
+                None => format!("{}[{}]", frame.field_path, frame.visited.len()),
+            };
+            if child.is_mapping() || child.is_sequence() {
+                stack.push(PruneFrame::new(key, field_path, child));
+            } else {
+                frame.visited.push((key, child));
+            }
+            continue;
+        }
+
+        let Some(frame) = stack.pop() else {
+            break;
+        };
+        let (key, pruned) = frame.finish(&mut removed);
+        match stack.last_mut() {
+            Some(parent) => parent.visited.push((key, pruned)),
+            None => *val = pruned,
+        }
+    }
+    removed
+}
+
+// A mapping or sequence being pruned: the children still to visit and the ones already pruned.
+// Children of sequences have no key.
+struct PruneFrame {
+    key: Option<Value>,
+    field_path: String,
+    is_mapping: bool,
+    pending: std::vec::IntoIter<(Option<Value>, Value)>,
+    visited: Vec<(Option<Value>, Value)>,
+}
+
+impl PruneFrame {
+    fn new(key: Option<Value>, field_path: String, val: Value) -> Self {
+        let is_mapping = val.is_mapping();
+        let pending: Vec<(Option<Value>, Value)> = match val {
+            Value::Mapping(map) => map.into_iter().map(|(k, v)| (Some(k), v)).collect(),
+            Value::Sequence(seq) => seq.into_iter().map(|v| (None, v)).collect(),
+            _ => Vec::new(),
+        };
+        PruneFrame { key, field_path, is_mapping, pending: pending.into_iter(), visited: Vec::new() }
+    }
+
+    // Rebuild the collection, dropping the mapping's empty children.
+    // Sequence items are kept as-is so list positions don't shift.
+    fn finish(self, removed: &mut Vec<String>) -> (Option<Value>, Value) {
+        if !self.is_mapping {
+            return (self.key, Value::Sequence(self.visited.into_iter().map(|(_, v)| v).collect()));
+        }
+
+        let mut map = serde_yaml::Mapping::new();
+        for (k, v) in self.visited.into_iter().filter_map(|(k, v)| Some((k?, v))) {
+            if is_empty_collection(&v) {
+                removed.push(path::join(&self.field_path, &k));
+            } else {
+                map.insert(k, v);
+            }
+        }
+        (self.key, Value::Mapping(map))
+    }
+}
+
+fn is_empty_collection(val: &Value) -> bool {
+    match val {
+        Value::Mapping(map) => map.is_empty(),
+        Value::Sequence(seq) => seq.is_empty(),
+        _ => false,
+    }
+}
@@ -0,0 +1,9 @@
+pub mod diff;
+pub mod fetch;
+pub mod input;
+pub mod legacy_migration;
+pub mod output;
+pub mod path;
+pub mod quantity;
+pub mod validation;
+pub mod version;
@@ -1,202 +1,656 @@
+use log::{error, info, warn, LevelFilter};
+use redpanda_chart_upgrade::diff;
+use redpanda_chart_upgrade::fetch::{self, CachingFetcher, ChartFetcher, HttpChartFetcher, OciChartFetcher, RetryingFetcher, StaticFetcher};
+use redpanda_chart_upgrade::input::{self, SetOverride};
+use redpanda_chart_upgrade::legacy_migration::{self, MergeStrategy, MigrationOptions};
+use redpanda_chart_upgrade::output::{self, OutputFormat};
+use redpanda_chart_upgrade::path;
+use redpanda_chart_upgrade::validation::{self, ReportFormat, ValidationOptions, ValidationReport};
+use redpanda_chart_upgrade::version::SchemaVersion;
 use serde_yaml::Value;
 use std::env;
+use std::fmt::Display;
 use std::fs;
 use std::fs::File;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process;
-use reqwest;
-
-const LATEST_CHART_VALUES_URL: &str = "https://raw.githubusercontent.com/redpanda-data/helm-charts/main/charts/redpanda/values.yaml";
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 
 #[tokio::main]
 async fn main() {
-    // Get the path to the existing deployment config file
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
+    // Get the path to the existing deployment config file and any options
+    let mut options = parse_args(env::args().skip(1));
+
+    // Report as annotations when running in GitHub Actions, unless a format was chosen
+    if options.report_format.is_none() && env::var("GITHUB_ACTIONS").as_deref() == Ok("true") {
+        options.report_format = Some(ReportFormat::Github);
+    }
+
+    // Log at info level unless overridden with RUST_LOG, quiet mode only logs errors
+    let mut logger = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"));
+    if options.quiet {
+        logger.filter_level(LevelFilter::Error);
+    }
+    logger.format_timestamp(None).format_target(false).init();
+
+    if options.input_paths.is_empty() {
         eprintln!("Provide the path to the existing deployment's values.yaml file:");
-        process::exit(1);
+        CliExit::Parse.exit();
+    }
+
+    // Never overwrite an input file by accident, it may be the only copy of the deployment's config
+    if let Some(report_file) = options.report_file.as_deref().filter(|report_file| is_input_file(&options, report_file)) {
+        if !options.force {
+            eprintln!("--report-file {} is one of the input files; pass --force to overwrite it", report_file);
+            CliExit::Parse.exit();
+        }
+        warn!("Overwriting input file {} with the report (--force)", report_file);
+    }
+
+    match options.command {
+        Command::Migrate => migrate(&options).await,
+        Command::Validate => validate(&options),
+    }
+}
+
+// Migrate the existing deployment config onto the latest chart values and write the result
+async fn migrate(options: &Options) {
+    // Use the bundled chart snapshot when offline
+    if options.offline {
+        if options.target_version.is_some() {
+            warn!("--target-version is ignored with --offline");
+        }
+        info!("Offline: using the bundled, hand-trimmed redpanda chart snapshot {}", fetch::BUNDLED_CHART_VERSION);
+        return migrate_from(options, &StaticFetcher::bundled()).await;
     }
-    let file1_path = &args[1];
 
-    // Read the existing deployment config file
-    let file1 = fs::read_to_string(file1_path).expect("Failed to read the first YAML file");
+    // --no-cache still refreshes the cache, it just never reads from it
+    let cache_ttl = if options.no_cache { Duration::ZERO } else { options.cache_ttl };
 
-    // Fetch the latest config file from the URL
-    let file2 = reqwest::get(LATEST_CHART_VALUES_URL)
-        .await
-        .expect("Failed to fetch YAML from URL")
-        .text()
-        .await
-        .expect("Failed to read the YAML content");
+    // Pull the chart from an OCI registry instead of GitHub
+    if let Some(reference) = &options.oci {
+        if options.target_version.is_some() {
+            warn!("--target-version is ignored with --oci, the reference names the chart version");
+        }
+        let fetcher = OciChartFetcher::new(reference, options.timeout).unwrap_or_else(|e| fail(CliExit::Parse, e));
+        let fetcher = RetryingFetcher::new(fetcher, options.fetch_retries, fetch::DEFAULT_BACKOFF);
+        let fetcher = CachingFetcher::new(fetcher, &fetch::default_cache_dir(), reference, cache_ttl);
+        return migrate_from(options, &fetcher).await;
+    }
 
-    // Parse both YAML files
-    let mut data1: Value = serde_yaml::from_str(&file1).expect("Failed to parse the existing deployment config file");
-    let data2: Value = serde_yaml::from_str(&file2).expect("Failed to parse the latest config file from the URL");
+    // Fetch from the target chart, the latest one unless a version was given
+    let fetcher = HttpChartFetcher::new(options.target_version, options.timeout)
+        .unwrap_or_else(|e| fail(CliExit::Network, format!("Failed to create the HTTP client: {}", e)));
+    let source = fetcher.file_url("");
+    let fetcher = RetryingFetcher::new(fetcher, options.fetch_retries, fetch::DEFAULT_BACKOFF);
+    let fetcher = CachingFetcher::new(fetcher, &fetch::default_cache_dir(), &source, cache_ttl);
+    migrate_from(options, &fetcher).await
+}
 
-    // Rename the specified keys in data1
-    rename_nested_keys(&mut data1);
+// Migrate the existing deployment config onto the chart served by the fetcher
+async fn migrate_from(options: &Options, fetcher: &(impl ChartFetcher + Sync)) {
+    let mut timings = Timings::default();
+    let (raw_inputs, documents) = load_inputs(options, &mut timings);
 
-    // Print the differences between the two YAML files
-    println!("Differences between the two files:");
-    print_diffs(&data1, &data2, 0);
+    // A multi-document input is migrated document by document and written back as one YAML stream
+    let multi_document = documents.len() > 1;
+    if multi_document && options.output_format != OutputFormat::Yaml {
+        fail(CliExit::Parse, "Multi-document input can only be written as YAML");
+    }
+    if multi_document && options.patch {
+        fail(CliExit::Parse, "--patch does not support multi-document input");
+    }
 
-    // Merge the second YAML file into the first, keeping data1's values
-    merge(&mut data1, &data2);
+    let overrides = load_overrides(options);
 
-    // Serialize the merged YAML to a string
-    let updated_yaml = serde_yaml::to_string(&data1).expect("Failed to serialize the updated YAML");
+    let started = Instant::now();
+    let values = fetcher.fetch_values().await.unwrap_or_else(|e| fail(CliExit::Network, e));
+    timings.record(format!("fetch chart values ({} bytes)", values.len()), started);
 
-    // Write the merged YAML to a file with a unique name
-    let output_file = get_unique_filename("updated-values.yaml");
-    let mut file = File::create(&output_file).expect("Failed to create the output file");
-    file.write_all(updated_yaml.as_bytes()).expect("Failed to write to the output file");
+    let started = Instant::now();
+    let data2 = fetch::parse_chart_values(&values).unwrap_or_else(|e| fail(CliExit::Parse, e));
+    timings.record("parse chart values", started);
 
-    println!("\nMerged YAML written to: {}", output_file);
-}
+    let chart_metadata = fetch::chart_metadata(fetcher).await;
+    let target_app_version = chart_metadata
+        .as_ref()
+        .and_then(|chart| chart.get("appVersion"))
+        .and_then(Value::as_str)
+        .and_then(|app_version| app_version.parse::<SchemaVersion>().ok());
+    if target_app_version.is_none() {
+        warn!("Could not determine the target chart's app version; skipping the image tag check");
+    }
+    let validation_options = ValidationOptions {
+        target_app_version,
+        expected_image_repository: options.expected_image_repository.clone(),
+        // Only a full merge guarantees every chart block is present
+        merged_with_chart: !options.no_merge
+            && options.merge_strategy == MergeStrategy::KeepExisting
+            && options.merge_include_only.is_empty()
+            && options.merge_exclude.is_empty(),
+    };
 
-// Recursive function to print differences between two YAML values
-fn print_diffs(val1: &Value, val2: &Value, indent: usize) {
-    match (val1, val2) {
-        (Value::Mapping(map1), Value::Mapping(map2)) => {
-            for (k, v1) in map1 {
-                if let Some(v2) = map2.get(k) {
-                    print_diffs(v1, v2, indent + 2);
-                } else {
-                    println!(
-                        "{}Key '{}' is only in the existing deployment config.",
-                        " ".repeat(indent),
-                        k.as_str().unwrap_or("<unknown key>")
-                    );
-                }
-            }
-            for k in map2.keys() {
-                if !map1.contains_key(k) {
-                    println!(
-                        "{}Key '{}' is only in the latest config.",
-                        " ".repeat(indent),
-                        k.as_str().unwrap_or("<unknown key>")
-                    );
-                }
-            }
+    // Rename the specified keys in data1 and merge data2 into it, keeping data1's values
+    let migration_options = MigrationOptions {
+        print_diffs: !options.quiet,
+        keep_empty: options.keep_empty,
+        skip_merge: options.no_merge,
+        merge_exclude: options.merge_exclude.clone(),
+        merge_include_only: options.merge_include_only.clone(),
+        merge_strategy: options.merge_strategy,
+    };
+    // Chart defaults to compare against, pruned like the output so empty values don't count as overrides
+    let defaults = (options.split_output || options.compact).then(|| {
+        let mut defaults = data2.clone();
+        if !options.keep_empty {
+            legacy_migration::prune_empty(&mut defaults);
         }
-        _ => {
-            if val1 != val2 {
-                println!(
-                    "{}Key has different values. existing: '{:?}' vs latest: '{:?}'.",
-                    " ".repeat(indent),
-                    val1,
-                    val2
-                );
-            }
+        defaults
+    });
+
+    let mut migrated = Vec::with_capacity(documents.len());
+    let mut summaries = Vec::with_capacity(documents.len());
+    let mut report = ValidationReport::default();
+    let mut patch = None;
+    for (index, data1) in documents.into_iter().enumerate() {
+        let before = options.patch.then(|| data1.clone());
+        let started = Instant::now();
+        let (mut data1, summary) = legacy_migration::migrate_with_options(data1, data2.clone(), &migration_options);
+        timings.record(if multi_document { format!("migrate document {}", index) } else { "migrate".to_string() }, started);
+
+        // --overrides files win over both the input and the chart defaults
+        if !overrides.is_empty() {
+            data1 = legacy_migration::merge_layers(std::iter::once(data1).chain(overrides.iter().cloned()));
+            legacy_migration::quote_string_fields(&mut data1);
+        }
+
+        // Validate the merged config
+        let mut document_report = validation::validate(&data1, &validation_options);
+        if multi_document {
+            document_report.prefix_paths(&format!("documents[{}]", index));
+        }
+        report.extend(document_report);
+
+        // With --compact only keep what differs from the chart defaults
+        if let (true, Some(defaults)) = (options.compact, &defaults) {
+            data1 = diff::overrides(&data1, defaults);
+        }
+
+        // Sort keys so repeated runs produce stable output
+        if options.sort_keys {
+            output::sort_keys(&mut data1);
         }
+
+        patch = before.map(|before| diff::json_patch(&before, &data1));
+        migrated.push(data1);
+        summaries.push(summary);
+    }
+
+    if let Some(report_file) = &options.report_file {
+        write_report_file(report_file, &validation::format_report(&report, options.report_format.unwrap_or_default()));
+    } else if let Some(format) = options.report_format {
+        print!("{}", validation::format_report(&report, format));
+    } else {
+        log_report(&report);
+    }
+
+    info!(
+        "Added: {}, Removed: {}, Migrated: {}, Warnings: {}",
+        summaries.iter().map(|summary| summary.added).sum::<usize>(),
+        summaries.iter().map(|summary| summary.removed).sum::<usize>(),
+        summaries.iter().map(|summary| summary.migrated).sum::<usize>(),
+        report.warnings.len()
+    );
+
+    // Write the merged config, and with --split-output just the keys that differ from the chart defaults
+    let chart_version = chart_metadata.as_ref().and_then(|chart| chart.get("version")).and_then(Value::as_str);
+    let inputs = options.input_paths.iter().map(String::as_str).zip(raw_inputs.iter().map(Vec::as_slice)).collect();
+    let provenance = Provenance { inputs, chart_version };
+    let output_file = write_values("updated-values", &migrated, options, &provenance, &mut timings);
+    if !options.quiet {
+        println!("\nMerged config written to: {}", output_file.display());
     }
-}
 
-// Recursive function to merge YAML values, keeping the first file's values
-fn merge(val1: &mut Value, val2: &Value) {
-    if let (Value::Mapping(map1), Value::Mapping(map2)) = (val1, val2) {
-        for (k, v2) in map2 {
-            let entry = map1.entry(k.clone()).or_insert(v2.clone());
+    if let (true, Some(defaults)) = (options.split_output, defaults) {
+        let overrides: Vec<Value> = migrated.iter().map(|data1| diff::overrides(data1, &defaults)).collect();
+        let overrides_file = write_values("updated-values.overrides", &overrides, options, &provenance, &mut timings);
+        if !options.quiet {
+            println!("Overrides of the chart defaults written to: {}", overrides_file.display());
+        }
+    }
 
-            // Avoid moving `entry`, only check its reference
-            if let Value::Mapping(_) = entry {
-                if let Value::Mapping(_) = v2 {
-                    // Recursively merge nested mappings
-                    merge(entry, v2);
+    // Describe the migration as a JSON Patch against the input, for GitOps tooling
+    if let Some(patch) = patch {
+        let patch_file = write_patch(&patch, options);
+        if !options.quiet {
+            println!("JSON Patch of the changes written to: {}", patch_file.display());
+        }
+    }
+
+    // List every removed key with the reason it was removed
+    if options.explain_removals {
+        println!("\nRemoved fields:");
+        for (index, summary) in summaries.iter().enumerate() {
+            for removed in &summary.removed_fields {
+                if multi_document {
+                    println!("  documents[{}].{}: {}", index, removed.field_path, removed.reason);
+                } else {
+                    println!("  {}: {}", removed.field_path, removed.reason);
                 }
             }
         }
     }
-}
 
-// Function to check for file existence and create a unique filename
-fn get_unique_filename(base_name: &str) -> String {
-    let mut count = 0;
-    let mut file_name = base_name.to_string();
+    // Hand the output to an external check such as "helm lint"
+    if let Some(command) = &options.post_check {
+        let started = Instant::now();
+        run_post_check(command, &output_file);
+        timings.record("post-check", started);
+    }
 
-    while Path::new(&file_name).exists() {
-        count += 1;
-        file_name = format!("updated-values-{}.yaml", count);
+    if options.timings {
+        timings.print();
     }
 
-    file_name
+    // In strict mode, fail when validation found problems
+    if options.strict && (report.has_errors() || report.has_warnings()) {
+        CliExit::Validation.exit();
+    }
 }
 
-fn rename_nested_keys(val: &mut Value) {
-    if let Value::Mapping(map) = val {
-        // Recursively traverse the nested mappings
-        for (_, v) in map.iter_mut() {
-            rename_nested_keys(v);
+// Validate the existing deployment config as-is, without migrating it
+fn validate(options: &Options) {
+    let mut timings = Timings::default();
+    let (_, documents) = load_inputs(options, &mut timings);
+
+    let started = Instant::now();
+    let validation_options = ValidationOptions { expected_image_repository: options.expected_image_repository.clone(), ..Default::default() };
+    let mut report = ValidationReport::default();
+    for (index, data1) in documents.iter().enumerate() {
+        let mut document_report = validation::validate(data1, &validation_options);
+        if documents.len() > 1 {
+            document_report.prefix_paths(&format!("documents[{}]", index));
         }
+        report.extend(document_report);
+    }
+    timings.record("validate", started);
+    if options.timings {
+        timings.print();
+    }
+    let formatted = validation::format_report(&report, options.report_format.unwrap_or_default());
+    match &options.report_file {
+        Some(report_file) => write_report_file(report_file, &formatted),
+        None => print!("{}", formatted),
+    }
 
-        // Move keys from "storage.tieredConfig.*" to "storage.tiered.config.*"
-        if let Some(Value::Mapping(tiered_config_map)) = map.remove(&Value::String("tieredConfig".to_string())) {
-            if let Some(Value::Mapping(tiered_map)) = map.get_mut(&Value::String("tiered".to_string())) {
-                let config_entry = tiered_map
-                    .entry(Value::String("config".to_string()))
-                    .or_insert_with(|| Value::Mapping(serde_yaml::Mapping::new()));
+    if report.has_errors() {
+        CliExit::Validation.exit();
+    }
+}
 
-                if let Value::Mapping(ref mut config_map) = config_entry {
-                    for (k, v) in tiered_config_map {
-                        config_map.insert(k, v);
-                    }
-                }
+// Read and parse the existing deployment config files, later files override earlier ones.
+// A single file may hold several documents, which are kept apart. The files' bytes are returned as read.
+fn load_inputs(options: &Options, timings: &mut Timings) -> (Vec<Vec<u8>>, Vec<Value>) {
+    let raw_inputs: Vec<Vec<u8>> = options
+        .input_paths
+        .iter()
+        .map(|path| fs::read(path).unwrap_or_else(|e| fail(CliExit::Io, format!("Failed to read {}: {}", path, e))))
+        .collect();
+    let file1s: Vec<String> = raw_inputs
+        .iter()
+        .zip(&options.input_paths)
+        // Gzipped files are decompressed transparently
+        .map(|(bytes, path)| input::decode_values(bytes, path).unwrap_or_else(|e| fail(CliExit::Parse, format!("Failed to decode {}: {}", path, e))))
+        .map(|contents| {
+            // Fill in ${VAR} placeholders from the environment
+            if options.expand_env {
+                input::expand_env_vars(&contents, |name| env::var(name).ok())
             } else {
-                let mut new_tiered_map = serde_yaml::Mapping::new();
-                let mut new_config_map = serde_yaml::Mapping::new();
-                for (k, v) in tiered_config_map {
-                    new_config_map.insert(k, v);
-                }
-                new_tiered_map.insert(Value::String("config".to_string()), Value::Mapping(new_config_map));
-                map.insert(Value::String("tiered".to_string()), Value::Mapping(new_tiered_map));
+                contents
             }
+        })
+        .collect();
+
+    // The existing deployment configs may be YAML or JSON
+    let mut layers: Vec<Vec<Value>> = file1s
+        .iter()
+        .zip(&options.input_paths)
+        .map(|(file1, path)| {
+            let started = Instant::now();
+            let documents = input::parse_documents(file1, path).unwrap_or_else(|e| fail(CliExit::Parse, format!("Failed to parse {}: {}", path, e)));
+            timings.record(format!("parse {}", path), started);
+            documents
+        })
+        .collect();
+    let mut documents = if layers.len() == 1 {
+        layers.remove(0)
+    } else if let Some(path) = layers.iter().zip(&options.input_paths).find(|(documents, _)| documents.len() > 1).map(|(_, path)| path) {
+        fail(CliExit::Parse, format!("{} holds several YAML documents, which can't be layered with other input files", path))
+    } else {
+        vec![legacy_migration::merge_layers(layers.into_iter().flatten())]
+    };
+
+    // Apply --set overrides on top of the files
+    for data1 in &mut documents {
+        for set_override in &options.set_overrides {
+            // With --preserve-null, "path=null" sets an explicit null like helm --set does, instead of the string "null"
+            let value = if options.preserve_null && set_override.null { Value::Null } else { set_override.value.clone() };
+            path::set(data1, &set_override.path, value);
         }
+    }
 
-        // Rename "storage.tieredStorageHostPath" -> "storage.tiered.hostPath"
-        if let Some(tiered_storage_host_path) = map.remove(&Value::String("tieredStorageHostPath".to_string())) {
-            if let Some(Value::Mapping(tiered_map)) = map.get_mut(&Value::String("tiered".to_string())) {
-                tiered_map.insert(Value::String("hostPath".to_string()), tiered_storage_host_path);
-            }
+    (raw_inputs, documents)
+}
+
+// Read and parse the --overrides files, which are layered over the migrated config
+fn load_overrides(options: &Options) -> Vec<Value> {
+    options
+        .override_paths
+        .iter()
+        .map(|path| {
+            let bytes = fs::read(path).unwrap_or_else(|e| fail(CliExit::Io, format!("Failed to read {}: {}", path, e)));
+            let contents = input::decode_values(&bytes, path).unwrap_or_else(|e| fail(CliExit::Parse, format!("Failed to decode {}: {}", path, e)));
+            input::parse_values(&contents, path).unwrap_or_else(|e| fail(CliExit::Parse, format!("Failed to parse {}: {}", path, e)))
+        })
+        .collect()
+}
+
+// Run a shell command with the output file appended as its last argument, failing the run when it fails.
+// Its stdout and stderr are passed through.
+fn run_post_check(command: &str, output_file: &Path) {
+    info!("Running post-check: {} {}", command, output_file.display());
+    let status = process::Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$1\"", command))
+        .arg("sh")
+        .arg(output_file)
+        .status()
+        .unwrap_or_else(|e| fail(CliExit::PostCheck, format!("Failed to run post-check '{}': {}", command, e)));
+
+    match status.code() {
+        Some(0) => info!("Post-check passed"),
+        Some(code) => fail(CliExit::PostCheck, format!("Post-check '{}' failed with exit code {}", command, code)),
+        None => fail(CliExit::PostCheck, format!("Post-check '{}' was killed by a signal", command)),
+    }
+}
+
+// What the provenance header records about a run
+struct Provenance<'a> {
+    // Each input file's path and its bytes as read from disk
+    inputs: Vec<(&'a str, &'a [u8])>,
+    chart_version: Option<&'a str>,
+}
+
+// Serialize the configs and write them to a new file named after `stem` in the output directory
+fn write_values(stem: &str, documents: &[Value], options: &Options, provenance: &Provenance, timings: &mut Timings) -> PathBuf {
+    let started = Instant::now();
+    let mut serialized = output::serialize_documents(documents, options.output_format)
+        .unwrap_or_else(|e| fail(CliExit::Parse, format!("Failed to serialize the updated config: {}", e)));
+    timings.record(format!("serialize {}", stem), started);
+
+    // Record where the output came from, JSON has no comments to carry it
+    if options.provenance {
+        if options.output_format != OutputFormat::Json {
+            serialized = output::with_provenance_header(&serialized, &provenance.inputs, provenance.chart_version);
+        } else {
+            warn!("--provenance is not supported for JSON output; skipping the header");
         }
+    }
 
-        // Rename "storage.tieredStoragePersistentVolume" -> "storage.tiered.persistentVolume"
-        if let Some(tiered_storage_pv) = map.remove(&Value::String("tieredStoragePersistentVolume".to_string())) {
-            if let Some(Value::Mapping(tiered_map)) = map.get_mut(&Value::String("tiered".to_string())) {
-                tiered_map.insert(Value::String("persistentVolume".to_string()), tiered_storage_pv);
-            }
+    let mut file_name = format!("{}.{}", stem, options.output_format.extension());
+    let bytes = if options.gzip_output {
+        file_name.push_str(".gz");
+        output::gzip(&serialized).unwrap_or_else(|e| fail(CliExit::Io, format!("Failed to compress the updated config: {}", e)))
+    } else {
+        serialized.into_bytes()
+    };
+
+    // Pick a unique name so earlier runs aren't overwritten
+    let output_dir = Path::new(options.output_dir.as_deref().unwrap_or(""));
+    fs::create_dir_all(output_dir).unwrap_or_else(|e| fail(CliExit::Io, format!("Failed to create {}: {}", output_dir.display(), e)));
+    let output_file = output::unique_output_path(output_dir, &file_name);
+    File::create(&output_file)
+        .and_then(|mut file| file.write_all(&bytes))
+        .unwrap_or_else(|e| fail(CliExit::Io, format!("Failed to write {}: {}", output_file.display(), e)));
+    output_file
+}
+
+// Write a JSON Patch to a new file in the output directory
+fn write_patch(patch: &[diff::PatchOp], options: &Options) -> PathBuf {
+    let serialized = serde_json::to_string_pretty(patch)
+        .map(|json| json + "\n")
+        .unwrap_or_else(|e| fail(CliExit::Parse, format!("Failed to serialize the JSON Patch: {}", e)));
+
+    let output_dir = Path::new(options.output_dir.as_deref().unwrap_or(""));
+    fs::create_dir_all(output_dir).unwrap_or_else(|e| fail(CliExit::Io, format!("Failed to create {}: {}", output_dir.display(), e)));
+    let patch_file = output::unique_output_path(output_dir, "updated-values.patch.json");
+    fs::write(&patch_file, serialized).unwrap_or_else(|e| fail(CliExit::Io, format!("Failed to write {}: {}", patch_file.display(), e)));
+    patch_file
+}
+
+// How long each stage of a run took, printed to stderr with --timings
+#[derive(Debug, Default)]
+struct Timings(Vec<(String, Duration)>);
+
+impl Timings {
+    fn record(&mut self, stage: impl Into<String>, started: Instant) {
+        self.0.push((stage.into(), started.elapsed()));
+    }
+
+    fn print(&self) {
+        eprintln!("Timings:");
+        for (stage, duration) in &self.0 {
+            eprintln!("  {}: {:.1?}", stage, duration);
         }
+    }
+}
 
-        // Move and rename keys inside "license_secret_ref" -> "enterprise.licenseSecretRef"
-        if let Some(Value::Mapping(mut license_secret_ref_map)) = map.remove(&Value::String("license_secret_ref".to_string())) {
-            // Rename "secret_name" -> "name" and "secret_key" -> "key" inside the object
-            if let Some(secret_name) = license_secret_ref_map.remove(&Value::String("secret_name".to_string())) {
-                license_secret_ref_map.insert(Value::String("name".to_string()), secret_name);
-            }
-            if let Some(secret_key) = license_secret_ref_map.remove(&Value::String("secret_key".to_string())) {
-                license_secret_ref_map.insert(Value::String("key".to_string()), secret_key);
-            }
+// Whether a path names the same file as one of the inputs, after resolving links and relative paths
+fn is_input_file(options: &Options, output_path: &str) -> bool {
+    let Ok(output_path) = fs::canonicalize(output_path) else {
+        return false;
+    };
+    options.input_paths.iter().filter_map(|input_path| fs::canonicalize(input_path).ok()).any(|input_path| input_path == output_path)
+}
 
-            // Move to "enterprise.licenseSecretRef"
-            let enterprise_entry = map
-                .entry(Value::String("enterprise".to_string()))
-                .or_insert_with(|| Value::Mapping(serde_yaml::Mapping::new()));
+// Write the formatted report to its own file, creating parent directories as needed
+fn write_report_file(report_file: &str, formatted: &str) {
+    let parent = Path::new(report_file).parent().filter(|parent| !parent.as_os_str().is_empty());
+    parent
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(report_file, formatted))
+        .unwrap_or_else(|e| fail(CliExit::Io, format!("Failed to write {}: {}", report_file, e)));
+}
 
-            if let Value::Mapping(enterprise_map) = enterprise_entry {
-                enterprise_map.insert(Value::String("licenseSecretRef".to_string()), Value::Mapping(license_secret_ref_map));
-            }
+fn log_report(report: &ValidationReport) {
+    for error in &report.errors {
+        error!("{}", error);
+    }
+    for warning in &report.warnings {
+        warn!("{}", warning);
+    }
+}
+
+// Exit codes, a stable contract for scripts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CliExit {
+    // The run completed; the process also exits with 0 by returning from main
+    #[allow(dead_code)]
+    Success = 0,
+    // An input file couldn't be read or the output couldn't be written
+    Io = 1,
+    // The arguments, input files or chart values couldn't be parsed
+    Parse = 2,
+    // The chart files couldn't be fetched
+    Network = 3,
+    // Validation found problems in --strict mode, or errors in the validate subcommand
+    Validation = 4,
+    // The --post-check command failed
+    PostCheck = 5,
+}
+
+impl CliExit {
+    fn exit(self) -> ! {
+        process::exit(self as i32)
+    }
+}
+
+// Log an error and exit with the given code
+fn fail(code: CliExit, message: impl Display) -> ! {
+    error!("{}", message);
+    code.exit()
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+enum Command {
+    #[default]
+    Migrate,
+    Validate,
+}
+
+#[derive(Debug)]
+struct Options {
+    command: Command,
+    input_paths: Vec<String>,
+    strict: bool,
+    sort_keys: bool,
+    output_format: OutputFormat,
+    quiet: bool,
+    provenance: bool,
+    keep_empty: bool,
+    preserve_null: bool,
+    expand_env: bool,
+    no_merge: bool,
+    report_format: Option<ReportFormat>,
+    merge_exclude: Vec<String>,
+    merge_include_only: Vec<String>,
+    target_version: Option<SchemaVersion>,
+    gzip_output: bool,
+    timeout: Duration,
+    explain_removals: bool,
+    set_overrides: Vec<SetOverride>,
+    report_file: Option<String>,
+    fetch_retries: u32,
+    cache_ttl: Duration,
+    no_cache: bool,
+    output_dir: Option<String>,
+    timings: bool,
+    split_output: bool,
+    offline: bool,
+    post_check: Option<String>,
+    patch: bool,
+    compact: bool,
+    oci: Option<String>,
+    force: bool,
+    expected_image_repository: Option<String>,
+    merge_strategy: MergeStrategy,
+    override_paths: Vec<String>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            command: Command::default(),
+            input_paths: Vec::new(),
+            strict: false,
+            sort_keys: false,
+            output_format: OutputFormat::default(),
+            quiet: false,
+            provenance: false,
+            keep_empty: false,
+            preserve_null: false,
+            expand_env: false,
+            no_merge: false,
+            report_format: None,
+            merge_exclude: Vec::new(),
+            merge_include_only: Vec::new(),
+            target_version: None,
+            gzip_output: false,
+            timeout: fetch::DEFAULT_TIMEOUT,
+            explain_removals: false,
+            set_overrides: Vec::new(),
+            report_file: None,
+            fetch_retries: fetch::DEFAULT_RETRIES,
+            cache_ttl: fetch::DEFAULT_CACHE_TTL,
+            no_cache: false,
+            output_dir: None,
+            timings: false,
+            split_output: false,
+            offline: false,
+            post_check: None,
+            patch: false,
+            compact: false,
+            oci: None,
+            force: false,
+            expected_image_repository: None,
+            merge_strategy: MergeStrategy::default(),
+            override_paths: Vec::new(),
         }
+    }
+}
 
-        // Rename "license_key" -> "enterprise.license"
-        if let Some(license_key) = map.remove(&Value::String("license_key".to_string())) {
-            let enterprise_entry = map
-                .entry(Value::String("enterprise".to_string()))
-                .or_insert_with(|| Value::Mapping(serde_yaml::Mapping::new()));
+// Parse command line arguments into options
+fn parse_args(args: impl IntoIterator<Item = String>) -> Options {
+    let mut options = Options::default();
 
-            if let Value::Mapping(enterprise_map) = enterprise_entry {
-                enterprise_map.insert(Value::String("license".to_string()), license_key);
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--strict" => options.strict = true,
+            "--sort-keys" => options.sort_keys = true,
+            "--quiet" | "-q" => options.quiet = true,
+            "--provenance" => options.provenance = true,
+            "--keep-empty" => options.keep_empty = true,
+            "--preserve-null" => options.preserve_null = true,
+            "--expand-env" => options.expand_env = true,
+            "--no-merge" => options.no_merge = true,
+            "--gzip-output" => options.gzip_output = true,
+            "--explain-removals" => options.explain_removals = true,
+            "--timings" => options.timings = true,
+            "--split-output" => options.split_output = true,
+            "--offline" => options.offline = true,
+            "--patch" => options.patch = true,
+            "--compact" => options.compact = true,
+            "--force" => options.force = true,
+            "--set" => options.set_overrides.push(parse_value(&arg, args.next())),
+            "--output-format" => options.output_format = parse_value(&arg, args.next()),
+            "--report" => options.report_format = Some(parse_value(&arg, args.next())),
+            "--output-dir" => options.output_dir = Some(parse_value(&arg, args.next())),
+            "--report-file" => options.report_file = Some(parse_value(&arg, args.next())),
+            "--post-check" => options.post_check = Some(parse_value(&arg, args.next())),
+            "--oci" => options.oci = Some(parse_value(&arg, args.next())),
+            "--image-repository" => options.expected_image_repository = Some(parse_value(&arg, args.next())),
+            "--overrides" => options.override_paths.push(parse_value(&arg, args.next())),
+            "--merge-exclude" => options.merge_exclude.push(parse_value(&arg, args.next())),
+            "--fetch-retries" => options.fetch_retries = parse_value(&arg, args.next()),
+            "--cache-ttl" => options.cache_ttl = parse_value::<humantime::Duration, _>(&arg, args.next()).into(),
+            "--no-cache" => options.no_cache = true,
+            "--timeout" => options.timeout = parse_value::<humantime::Duration, _>(&arg, args.next()).into(),
+            "--target-version" => options.target_version = Some(parse_value(&arg, args.next())),
+            "--merge-strategy" => options.merge_strategy = parse_value(&arg, args.next()),
+            "--merge-include-only" => options.merge_include_only.push(parse_value(&arg, args.next())),
+            flag if flag.starts_with('-') => {
+                eprintln!("Unknown option: {}", flag);
+                CliExit::Parse.exit();
             }
+            "validate" if options.input_paths.is_empty() && options.command == Command::Migrate => options.command = Command::Validate,
+            _ => options.input_paths.push(arg),
         }
     }
+
+    options
 }
 
+// Parse the value following a flag, exiting with a usage error if it's missing or invalid
+fn parse_value<T: FromStr<Err = E>, E: Display>(flag: &str, value: Option<String>) -> T {
+    let Some(value) = value else {
+        eprintln!("Missing value for {}", flag);
+        CliExit::Parse.exit();
+    };
+    value.parse().unwrap_or_else(|e| {
+        eprintln!("Invalid value for {}: {}", flag, e);
+        CliExit::Parse.exit();
+    })
+}
@@ -0,0 +1,166 @@
+use crate::path;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde_yaml::Value;
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::SystemTime;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Yaml,
+    Json,
+    Toml,
+}
+
+impl OutputFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Yaml => "yaml",
+            OutputFormat::Json => "json",
+            OutputFormat::Toml => "toml",
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "yaml" => Ok(OutputFormat::Yaml),
+            "json" => Ok(OutputFormat::Json),
+            "toml" => Ok(OutputFormat::Toml),
+            other => Err(format!("Unknown output format '{}', expected yaml, json or toml", other)),
+        }
+    }
+}
+
+// Serialize a config in the requested output format
+pub fn serialize(val: &Value, format: OutputFormat) -> Result<String, String> {
+    match format {
+        OutputFormat::Yaml => serde_yaml::to_string(val).map_err(|e| e.to_string()),
+        OutputFormat::Json => serde_json::to_string_pretty(val)
+            .map(|json| json + "\n")
+            .map_err(|e| e.to_string()),
+        OutputFormat::Toml => match to_toml(val, "")? {
+            toml::Value::Table(table) => toml::to_string(&table).map_err(|e| e.to_string()),
+            _ => Err("TOML output needs a mapping at the top level".to_string()),
+        },
+    }
+}
+
+// Convert a config to TOML, which has no null and only string keys
+fn to_toml(val: &Value, field_path: &str) -> Result<toml::Value, String> {
+    match val {
+        Value::Null => Err(format!(
+            "{} is null, which TOML can't represent; remove it or set a value",
+            if field_path.is_empty() { "The config" } else { field_path }
+        )),
+        Value::Bool(b) => Ok(toml::Value::Boolean(*b)),
+        Value::Number(n) => match (n.as_i64(), n.as_f64()) {
+            (Some(i), _) => Ok(toml::Value::Integer(i)),
+            (None, Some(f)) if n.is_f64() => Ok(toml::Value::Float(f)),
+            _ => Err(format!("{} ({}) is too large for a TOML integer", field_path, n)),
+        },
+        Value::String(s) => Ok(toml::Value::String(s.clone())),
+        Value::Sequence(seq) => seq
+            .iter()
+            .enumerate()
+            .map(|(i, v)| to_toml(v, &format!("{}[{}]", field_path, i)))
+            .collect::<Result<_, _>>()
+            .map(toml::Value::Array),
+        Value::Mapping(map) => {
+            let mut table = toml::map::Map::new();
+            for (k, v) in map {
+                let field_path = path::join(field_path, k);
+                let key = match k {
+                    Value::String(s) => s.clone(),
+                    Value::Bool(_) | Value::Number(_) => key_to_string(k).trim_end().to_string(),
+                    _ => return Err(format!("{} has a key TOML can't represent", field_path)),
+                };
+                table.insert(key, to_toml(v, &field_path)?);
+            }
+            Ok(toml::Value::Table(table))
+        }
+        Value::Tagged(tagged) => to_toml(&tagged.value, field_path),
+    }
+}
+
+// Serialize several configs as one multi-document YAML stream, only YAML can hold more than one
+pub fn serialize_documents(vals: &[Value], format: OutputFormat) -> Result<String, String> {
+    match vals {
+        [val] => serialize(val, format),
+        _ if format == OutputFormat::Yaml => vals.iter().map(|val| serialize(val, format)).collect::<Result<Vec<_>, _>>().map(|documents| documents.join("---\n")),
+        _ => Err(format!("{} documents can only be written as YAML", vals.len())),
+    }
+}
+
+// Gzip serialized output
+pub fn gzip(serialized: &str) -> Result<Vec<u8>, String> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(serialized.as_bytes()).map_err(|e| e.to_string())?;
+    encoder.finish().map_err(|e| e.to_string())
+}
+
+// Pick a path for base_name in dir that doesn't exist yet, adding "-1", "-2", ... to the stem
+pub fn unique_output_path(dir: &Path, base_name: &str) -> PathBuf {
+    // Keep compound extensions such as ".yaml.gz" together
+    let (stem, extension) = match base_name.split_once('.') {
+        Some((stem, extension)) => (stem, format!(".{}", extension)),
+        None => (base_name, String::new()),
+    };
+
+    let mut count = 0;
+    let mut file_path = dir.join(base_name);
+
+    while file_path.exists() {
+        count += 1;
+        file_path = dir.join(format!("{}-{}{}", stem, count, extension));
+    }
+
+    file_path
+}
+
+// Sort mapping keys alphabetically at every level, leaving sequence order untouched.
+// Nested values are sorted from an explicit stack so deep configs can't overflow the call stack.
+pub fn sort_keys(val: &mut Value) {
+    let mut stack = vec![val];
+    while let Some(val) = stack.pop() {
+        match val {
+            Value::Mapping(map) => {
+                let mut entries: Vec<(Value, Value)> = std::mem::take(map).into_iter().collect();
+                entries.sort_by_key(|(k, _)| key_to_string(k));
+                map.extend(entries);
+                stack.extend(map.values_mut());
+            }
+            Value::Sequence(seq) => stack.extend(seq.iter_mut()),
+            _ => {}
+        }
+    }
+}
+
+fn key_to_string(key: &Value) -> String {
+    match key {
+        Value::String(s) => s.clone(),
+        other => serde_yaml::to_string(other).unwrap_or_default(),
+    }
+}
+
+// Prepend YAML comments recording how the output was generated. Each input is hashed as it is on disk,
+// so the hashes match "sha256sum" of the input files.
+pub fn with_provenance_header(serialized: &str, inputs: &[(&str, &[u8])], chart_version: Option<&str>) -> String {
+    let mut header = String::new();
+    header.push_str(&format!("# Generated by {} {}\n", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")));
+    header.push_str(&format!("# Source chart version: {}\n", chart_version.unwrap_or("unknown")));
+    header.push_str(&format!("# Generated at: {}\n", humantime::format_rfc3339_seconds(SystemTime::now())));
+    for (file_name, bytes) in inputs {
+        let input_hash: String = Sha256::digest(bytes).iter().map(|byte| format!("{:02x}", byte)).collect();
+        header.push_str(&format!("# Input SHA-256: {}  {}\n", input_hash, file_name));
+    }
+
+    header + serialized
+}
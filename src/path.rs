@@ -0,0 +1,52 @@
+use serde_yaml::{Mapping, Value};
+
+// Look up a value by its dotted path (e.g. "storage.tiered.config")
+pub fn get<'a>(val: &'a Value, path: &str) -> Option<&'a Value> {
+    let mut current = val;
+    for key in path.split('.') {
+        current = current.as_mapping()?.get(key)?;
+    }
+    Some(current)
+}
+
+// Mutable counterpart of `get`
+pub fn get_mut<'a>(val: &'a mut Value, path: &str) -> Option<&'a mut Value> {
+    let mut current = val;
+    for key in path.split('.') {
+        current = current.as_mapping_mut()?.get_mut(key)?;
+    }
+    Some(current)
+}
+
+// Set a value by its dotted path, creating intermediate mappings as needed
+pub fn set(val: &mut Value, path: &str, new_value: Value) {
+    let mut current = val;
+    for key in path.split('.') {
+        if !current.is_mapping() {
+            *current = Value::Mapping(Mapping::new());
+        }
+        let Value::Mapping(map) = current else {
+            unreachable!();
+        };
+        current = map.entry(Value::String(key.to_string())).or_insert(Value::Null);
+    }
+    *current = new_value;
+}
+
+// Remove a value by its dotted path, returning it if it was present
+pub fn remove(val: &mut Value, path: &str) -> Option<Value> {
+    match path.rsplit_once('.') {
+        Some((parent, key)) => get_mut(val, parent)?.as_mapping_mut()?.remove(key),
+        None => val.as_mapping_mut()?.remove(path),
+    }
+}
+
+// Append a mapping key to a dotted path
+pub fn join(prefix: &str, key: &Value) -> String {
+    let key = key.as_str().map(str::to_string).unwrap_or_else(|| format!("{:?}", key));
+    if prefix.is_empty() {
+        key
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
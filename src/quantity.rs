@@ -0,0 +1,72 @@
+use serde_yaml::Value;
+
+// Parse a Kubernetes CPU quantity ("2", "0.5", "500m") into millicores
+pub fn parse_cpu(val: &Value) -> Option<f64> {
+    match val {
+        Value::Number(n) => n.as_f64().map(|cores| cores * 1000.0),
+        Value::String(s) => {
+            let s = s.trim();
+            match s.strip_suffix('m') {
+                Some(millis) => millis.parse::<f64>().ok(),
+                None => s.parse::<f64>().ok().map(|cores| cores * 1000.0),
+            }
+        }
+        _ => None,
+    }
+}
+
+// Parse a Kubernetes memory quantity ("2Gi", "512Mi", "2.5Gi", "1048576") into bytes
+pub fn parse_memory(val: &Value) -> Option<f64> {
+    const SUFFIXES: [(&str, f64); 7] = [
+        ("Ki", 1024.0),
+        ("Mi", 1024.0 * 1024.0),
+        ("Gi", 1024.0 * 1024.0 * 1024.0),
+        ("Ti", 1024.0 * 1024.0 * 1024.0 * 1024.0),
+        ("k", 1000.0),
+        ("M", 1000.0 * 1000.0),
+        ("G", 1000.0 * 1000.0 * 1000.0),
+    ];
+
+    match val {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => {
+            let s = s.trim();
+            for (suffix, multiplier) in SUFFIXES {
+                if let Some(amount) = s.strip_suffix(suffix) {
+                    return amount.parse::<f64>().ok().map(|amount| amount * multiplier);
+                }
+            }
+            s.parse::<f64>().ok()
+        }
+        _ => None,
+    }
+}
+
+// Binary memory units, largest first
+const BINARY_UNITS: [(&str, f64); 4] = [
+    ("Ti", 1024.0 * 1024.0 * 1024.0 * 1024.0),
+    ("Gi", 1024.0 * 1024.0 * 1024.0),
+    ("Mi", 1024.0 * 1024.0),
+    ("Ki", 1024.0),
+];
+
+// Canonical millicpu form of a CPU quantity, so "1" and "1000m" both become "1000m"
+pub fn canonical_cpu(val: &Value) -> Option<String> {
+    let millis = parse_cpu(val)?;
+    (millis >= 0.0 && millis.fract() == 0.0).then(|| format!("{}m", millis))
+}
+
+// Canonical forms of memory quantities that are compared with each other, in the largest binary
+// unit that expresses all of them as whole numbers, e.g. "1Gi" and "1536Mi" become "1024Mi" and "1536Mi"
+pub fn canonical_memory(vals: &[&Value]) -> Option<Vec<String>> {
+    let bytes: Vec<f64> = vals.iter().map(|val| parse_memory(val)).collect::<Option<_>>()?;
+    if bytes.iter().any(|bytes| *bytes < 0.0 || bytes.fract() != 0.0) {
+        return None;
+    }
+
+    let (suffix, multiplier) = BINARY_UNITS
+        .into_iter()
+        .find(|(_, multiplier)| bytes.iter().all(|bytes| (bytes / multiplier).fract() == 0.0))
+        .unwrap_or(("", 1.0));
+    Some(bytes.iter().map(|bytes| format!("{}{}", bytes / multiplier, suffix)).collect())
+}
@@ -0,0 +1,886 @@
+use crate::path;
+use crate::quantity;
+use crate::version::SchemaVersion;
+use log::info;
+use serde::Serialize;
+use serde_yaml::Value;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum ValidationErrorType {
+    StructureViolation,
+    InvalidFieldValue,
+    MissingRequiredField,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValidationError {
+    pub error_type: ValidationErrorType,
+    pub field_path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} at '{}': {}", self.error_type, self.field_path, self.message)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum ValidationWarningType {
+    PotentialIssue,
+    SuboptimalConfiguration,
+    DeprecatedField,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValidationWarning {
+    pub warning_type: ValidationWarningType,
+    pub field_path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} at '{}': {}", self.warning_type, self.field_path, self.message)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ValidationReport {
+    pub errors: Vec<ValidationError>,
+    pub warnings: Vec<ValidationWarning>,
+}
+
+impl ValidationReport {
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+
+    pub fn has_warnings(&self) -> bool {
+        !self.warnings.is_empty()
+    }
+
+    pub fn extend(&mut self, other: ValidationReport) {
+        self.errors.extend(other.errors);
+        self.warnings.extend(other.warnings);
+    }
+
+    // Prefix every field path, e.g. with the document the findings are about
+    pub fn prefix_paths(&mut self, prefix: &str) {
+        let field_paths = self.errors.iter_mut().map(|error| &mut error.field_path);
+        for field_path in field_paths.chain(self.warnings.iter_mut().map(|warning| &mut warning.field_path)) {
+            *field_path = format!("{}.{}", prefix, field_path);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReportFormat {
+    #[default]
+    Console,
+    Json,
+    // GitHub Actions workflow commands, shown as annotations on the run and its pull request
+    Github,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "console" => Ok(ReportFormat::Console),
+            "json" => Ok(ReportFormat::Json),
+            "github" => Ok(ReportFormat::Github),
+            other => Err(format!("Unknown report format '{}', expected console, json or github", other)),
+        }
+    }
+}
+
+// Render a validation report in the requested format
+pub fn format_report(report: &ValidationReport, format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Console => {
+            let mut out = String::new();
+            for error in &report.errors {
+                out.push_str(&format!("ERROR   {}\n", error));
+            }
+            for warning in &report.warnings {
+                out.push_str(&format!("WARNING {}\n", warning));
+            }
+            out.push_str(&format!("{} error(s), {} warning(s)\n", report.errors.len(), report.warnings.len()));
+            out
+        }
+        ReportFormat::Json => serde_json::to_string_pretty(report).expect("Failed to serialize the validation report") + "\n",
+        ReportFormat::Github => {
+            let mut out = String::new();
+            for error in &report.errors {
+                out.push_str(&github_annotation("error", &format!("{:?} at {}", error.error_type, error.field_path), &error.message));
+            }
+            for warning in &report.warnings {
+                out.push_str(&github_annotation("warning", &format!("{:?} at {}", warning.warning_type, warning.field_path), &warning.message));
+            }
+            out
+        }
+    }
+}
+
+// A "::error title=...::message" workflow command, escaped as GitHub Actions expects
+fn github_annotation(level: &str, title: &str, message: &str) -> String {
+    let escape_data = |s: &str| s.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A");
+    let escape_property = |s: &str| escape_data(s).replace(':', "%3A").replace(',', "%2C");
+    format!("::{} title={}::{}\n", level, escape_property(title), escape_data(message))
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ValidationOptions {
+    // The Redpanda version the target chart deploys, used to flag outdated image tags
+    pub target_app_version: Option<SchemaVersion>,
+    // The registry mirror image.repository is expected to use, instead of the official repositories
+    pub expected_image_repository: Option<String>,
+    // The config was merged with the chart defaults, so blocks the chart always has must be present
+    pub merged_with_chart: bool,
+}
+
+// Run every validation pass against a (migrated) config
+pub fn validate(config: &Value, options: &ValidationOptions) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    report.errors.extend(validate_field_types(config));
+    report.errors.extend(validate_listener_tls_certs(config));
+    report.errors.extend(validate_listener_ports(config));
+    if options.merged_with_chart {
+        report.errors.extend(validate_kafka_listener(config));
+    }
+    report.warnings.extend(validate_listener_tls_consistency(config));
+    report.errors.extend(validate_resource_requests(config));
+    report.warnings.extend(validate_minimum_memory(config));
+    report.extend(validate_tiered_storage(config));
+    report.warnings.extend(validate_deprecated_fields(config));
+    report.warnings.extend(validate_rack_awareness(config));
+    report.warnings.extend(validate_replica_parity(config));
+    report.warnings.extend(validate_persistent_storage(config));
+    report.warnings.extend(validate_developer_mode(config));
+    report.errors.extend(validate_cron_schedules(config));
+    report.warnings.extend(validate_image_repository(config, options.expected_image_repository.as_deref()));
+    if let Some(target) = &options.target_app_version {
+        report.warnings.extend(validate_image_tag(config, target));
+    }
+    report
+}
+
+// Old chart fields and where the migration moves them
+const DEPRECATED_FIELDS: [(&str, &str); 5] = [
+    ("storage.tieredConfig", "storage.tiered.config"),
+    ("storage.tieredStorageHostPath", "storage.tiered.hostPath"),
+    ("storage.tieredStoragePersistentVolume", "storage.tiered.persistentVolume"),
+    ("license_key", "enterprise.license"),
+    ("license_secret_ref", "enterprise.licenseSecretRef"),
+];
+
+// Flag fields from older charts that the latest chart no longer reads
+pub fn validate_deprecated_fields(config: &Value) -> Vec<ValidationWarning> {
+    DEPRECATED_FIELDS
+        .iter()
+        .filter(|(field_path, _)| path::get(config, field_path).is_some())
+        .map(|(field_path, replacement)| ValidationWarning {
+            warning_type: ValidationWarningType::DeprecatedField,
+            field_path: field_path.to_string(),
+            message: format!("{} is deprecated, use {} instead", field_path, replacement),
+        })
+        .collect()
+}
+
+// Fields the chart expects to be booleans
+pub const BOOLEAN_FIELDS: [&str; 12] = [
+    "tls.enabled",
+    "auth.sasl.enabled",
+    "rackAwareness.enabled",
+    "monitoring.enabled",
+    "external.enabled",
+    "console.enabled",
+    "statefulset.initContainers.tuning.enabled",
+    "storage.persistentVolume.enabled",
+    "storage.tiered.persistentVolume.enabled",
+    "storage.tiered.config.cloud_storage_enabled",
+    "storage.tiered.config.cloud_storage_enable_remote_read",
+    "storage.tiered.config.cloud_storage_enable_remote_write",
+];
+
+// Check that well-known fields have the type the chart expects
+pub fn validate_field_types(config: &Value) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    for field_path in BOOLEAN_FIELDS {
+        if let Some(value) = path::get(config, field_path) {
+            if !value.is_bool() {
+                errors.push(ValidationError {
+                    error_type: ValidationErrorType::InvalidFieldValue,
+                    field_path: field_path.to_string(),
+                    message: format!("Expected a boolean, found {:?}", value),
+                });
+            }
+        }
+    }
+
+    if let Some(replicas) = path::get(config, "statefulset.replicas") {
+        if !replicas.is_u64() {
+            errors.push(ValidationError {
+                error_type: ValidationErrorType::InvalidFieldValue,
+                field_path: "statefulset.replicas".to_string(),
+                message: format!("Expected a non-negative integer, found {:?}", replicas),
+            });
+        }
+    }
+
+    if let Some(Value::Mapping(listeners)) = path::get(config, "listeners") {
+        for (name, listener) in listeners {
+            if let Some(port) = listener.get("port") {
+                if !port.is_u64() {
+                    errors.push(ValidationError {
+                        error_type: ValidationErrorType::InvalidFieldValue,
+                        field_path: format!("listeners.{}.port", name.as_str().unwrap_or("<unknown listener>")),
+                        message: format!("Expected a port number, found {:?}", port),
+                    });
+                }
+            }
+        }
+    }
+
+    errors
+}
+
+// Check that no port is bound by two listeners, counting external and advertised ports too.
+// An external listener may advertise its own port, so only clashes between owners count.
+pub fn validate_listener_ports(config: &Value) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    let Some(Value::Mapping(listeners)) = path::get(config, "listeners") else {
+        return errors;
+    };
+
+    // (port, owning listener, field path) for every port in use
+    let mut ports: Vec<(u64, String, String)> = Vec::new();
+    for (name, listener) in listeners {
+        let Some(name) = name.as_str() else {
+            continue;
+        };
+        if listener.get("enabled").and_then(Value::as_bool) == Some(false) {
+            continue;
+        }
+        let owner = format!("listeners.{}", name);
+        if let Some(port) = listener.get("port").and_then(Value::as_u64) {
+            ports.push((port, owner.clone(), format!("{}.port", owner)));
+        }
+
+        let Some(Value::Mapping(externals)) = listener.get("external") else {
+            continue;
+        };
+        for (external_name, external) in externals {
+            let Some(external_name) = external_name.as_str() else {
+                continue;
+            };
+            if external.get("enabled").and_then(Value::as_bool) == Some(false) {
+                continue;
+            }
+            let external_owner = format!("{}.external.{}", owner, external_name);
+            if let Some(port) = external.get("port").and_then(Value::as_u64) {
+                ports.push((port, external_owner.clone(), format!("{}.port", external_owner)));
+            }
+            if let Some(Value::Sequence(advertised_ports)) = external.get("advertisedPorts") {
+                for port in advertised_ports.iter().filter_map(Value::as_u64) {
+                    ports.push((port, external_owner.clone(), format!("{}.advertisedPorts", external_owner)));
+                }
+            }
+        }
+    }
+
+    for (i, (port, owner, field_path)) in ports.iter().enumerate() {
+        if let Some((_, first_owner, _)) = ports[..i].iter().find(|(other_port, other_owner, _)| other_port == port && other_owner != owner) {
+            errors.push(ValidationError {
+                error_type: ValidationErrorType::StructureViolation,
+                field_path: field_path.clone(),
+                message: format!("Port {} is used by both {} and {}", port, first_owner, owner),
+            });
+        }
+    }
+    errors
+}
+
+// Check that every TLS-enabled listener references a cert that exists under "tls.certs"
+pub fn validate_listener_tls_certs(config: &Value) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    let Some(Value::Mapping(listeners)) = path::get(config, "listeners") else {
+        return errors;
+    };
+
+    let certs = path::get(config, "tls.certs").and_then(Value::as_mapping);
+    // The chart enables TLS globally unless told otherwise
+    let global_enabled = path::get(config, "tls.enabled").and_then(Value::as_bool).unwrap_or(true);
+
+    for (name, listener) in listeners {
+        let name = name.as_str().unwrap_or("<unknown listener>");
+        let listener_enabled = tls_enabled(listener, global_enabled);
+        check_tls_cert(listener, listener_enabled, certs, name, &format!("listeners.{}", name), &mut errors);
+
+        // External listeners inherit the TLS setting of their parent listener
+        if let Some(Value::Mapping(externals)) = listener.get("external") {
+            for (external_name, external) in externals {
+                let external_name = external_name.as_str().unwrap_or("<unknown listener>");
+                check_tls_cert(
+                    external,
+                    tls_enabled(external, listener_enabled),
+                    certs,
+                    &format!("{}.external.{}", name, external_name),
+                    &format!("listeners.{}.external.{}", name, external_name),
+                    &mut errors,
+                );
+            }
+        }
+    }
+
+    errors
+}
+
+// Client-facing listeners that normally share one TLS setting
+const CLIENT_LISTENERS: [&str; 3] = ["kafka", "http", "schemaRegistry"];
+
+// Warn when TLS is on for some client listeners and off for others, usually a leftover of the listener restructure
+pub fn validate_listener_tls_consistency(config: &Value) -> Vec<ValidationWarning> {
+    let mut warnings = Vec::new();
+    let global_enabled = path::get(config, "tls.enabled").and_then(Value::as_bool).unwrap_or(true);
+
+    let (with_tls, without_tls): (Vec<_>, Vec<_>) = CLIENT_LISTENERS
+        .iter()
+        .filter_map(|name| {
+            let listener = path::get(config, &format!("listeners.{}", name))?;
+            if listener.get("enabled").and_then(Value::as_bool) == Some(false) {
+                return None;
+            }
+            Some((*name, tls_enabled(listener, global_enabled)))
+        })
+        .partition(|(_, enabled)| *enabled);
+
+    if !with_tls.is_empty() && !without_tls.is_empty() {
+        let names = |listeners: &[(&str, bool)]| listeners.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", ");
+        warnings.push(ValidationWarning {
+            warning_type: ValidationWarningType::PotentialIssue,
+            field_path: "listeners".to_string(),
+            message: format!(
+                "TLS is enabled on {} but not on {}; check that this is intended",
+                names(&with_tls),
+                names(&without_tls)
+            ),
+        });
+    }
+
+    warnings
+}
+
+fn tls_enabled(listener: &Value, inherited: bool) -> bool {
+    path::get(listener, "tls.enabled").and_then(Value::as_bool).unwrap_or(inherited)
+}
+
+fn check_tls_cert(
+    listener: &Value,
+    enabled: bool,
+    certs: Option<&serde_yaml::Mapping>,
+    listener_name: &str,
+    listener_path: &str,
+    errors: &mut Vec<ValidationError>,
+) {
+    if !enabled {
+        return;
+    }
+    let Some(cert) = path::get(listener, "tls.cert").and_then(Value::as_str) else {
+        return;
+    };
+
+    if !certs.is_some_and(|certs| certs.contains_key(cert)) {
+        errors.push(ValidationError {
+            error_type: ValidationErrorType::StructureViolation,
+            field_path: format!("{}.tls.cert", listener_path),
+            message: format!(
+                "Listener '{}' references TLS cert '{}', which is not defined under tls.certs",
+                listener_name, cert
+            ),
+        });
+    }
+}
+
+type QuantityParser = fn(&Value) -> Option<f64>;
+
+// Check that no resource request under "resources.requests" exceeds its limit under "resources.limits"
+pub fn validate_resource_requests(config: &Value) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    let resources: [(&str, QuantityParser); 2] = [("cpu", quantity::parse_cpu), ("memory", quantity::parse_memory)];
+
+    for (resource, parse) in resources {
+        let request_path = format!("resources.requests.{}", resource);
+        let limit_path = format!("resources.limits.{}", resource);
+        let (Some(request), Some(limit)) = (path::get(config, &request_path), path::get(config, &limit_path)) else {
+            continue;
+        };
+
+        for (field_path, value) in [(&request_path, request), (&limit_path, limit)] {
+            if parse(value).is_none() {
+                errors.push(ValidationError {
+                    error_type: ValidationErrorType::InvalidFieldValue,
+                    field_path: field_path.clone(),
+                    message: format!("Cannot parse {} quantity {:?}", resource, value),
+                });
+            }
+        }
+
+        if let (Some(requested), Some(limited)) = (parse(request), parse(limit)) {
+            if requested > limited {
+                errors.push(ValidationError {
+                    error_type: ValidationErrorType::InvalidFieldValue,
+                    field_path: request_path,
+                    message: format!(
+                        "{} request {} exceeds its limit {}",
+                        resource,
+                        quantity_to_string(request),
+                        quantity_to_string(limit)
+                    ),
+                });
+            }
+        }
+    }
+
+    errors
+}
+
+fn quantity_to_string(val: &Value) -> String {
+    match val {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+// Memory fields of a broker, in both the Kubernetes and the chart's own resource format
+const MEMORY_FIELDS: [&str; 4] = [
+    "resources.requests.memory",
+    "resources.limits.memory",
+    "resources.memory.container.min",
+    "resources.memory.container.max",
+];
+
+// Redpanda's recommended minimum memory per broker (2Gi)
+const MIN_BROKER_MEMORY: f64 = 2.0 * 1024.0 * 1024.0 * 1024.0;
+
+// Warn when a broker's memory is below Redpanda's recommended minimum
+pub fn validate_minimum_memory(config: &Value) -> Vec<ValidationWarning> {
+    MEMORY_FIELDS
+        .iter()
+        .filter_map(|field_path| {
+            let value = path::get(config, field_path)?;
+            let bytes = quantity::parse_memory(value)?;
+            (bytes < MIN_BROKER_MEMORY).then(|| ValidationWarning {
+                warning_type: ValidationWarningType::SuboptimalConfiguration,
+                field_path: field_path.to_string(),
+                message: format!(
+                    "{} is {} ({} bytes), below the recommended minimum of 2Gi per broker",
+                    field_path,
+                    quantity_to_string(value),
+                    bytes
+                ),
+            })
+        })
+        .collect()
+}
+
+// Endpoint domains of object stores that support instance metadata credentials
+const MANAGED_STORAGE_DOMAINS: [&str; 2] = ["amazonaws.com", "googleapis.com"];
+
+// Check that tiered storage has everything it needs when "cloud_storage_enabled" is set
+pub fn validate_tiered_storage(config: &Value) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    let Some(tiered_config) = path::get(config, "storage.tiered.config") else {
+        return report;
+    };
+    if tiered_config.get("cloud_storage_enabled").and_then(Value::as_bool) != Some(true) {
+        return report;
+    }
+
+    for field in ["cloud_storage_bucket", "cloud_storage_region"] {
+        if is_blank(tiered_config.get(field)) {
+            report.errors.push(ValidationError {
+                error_type: ValidationErrorType::MissingRequiredField,
+                field_path: format!("storage.tiered.config.{}", field),
+                message: format!("{} is required when tiered storage is enabled", field),
+            });
+        }
+    }
+
+    // Static credentials are only read from the config when using "config_file" (the chart default)
+    let credentials_source = tiered_config
+        .get("cloud_storage_credentials_source")
+        .and_then(Value::as_str)
+        .unwrap_or("config_file");
+    if credentials_source == "config_file" {
+        for field in ["cloud_storage_access_key", "cloud_storage_secret_key"] {
+            if is_blank(tiered_config.get(field)) {
+                report.warnings.push(ValidationWarning {
+                    warning_type: ValidationWarningType::PotentialIssue,
+                    field_path: format!("storage.tiered.config.{}", field),
+                    message: format!(
+                        "{} is not set but cloud_storage_credentials_source is config_file; make sure the credentials are provided another way",
+                        field
+                    ),
+                });
+            }
+        }
+    }
+
+    // S3-compatible stores such as MinIO can't hand out credentials through instance metadata
+    if let Some(endpoint) = tiered_config.get("cloud_storage_api_endpoint").and_then(Value::as_str) {
+        let is_custom_endpoint = !endpoint.trim().is_empty() && !MANAGED_STORAGE_DOMAINS.iter().any(|domain| endpoint.contains(domain));
+        if is_custom_endpoint && credentials_source != "config_file" {
+            report.warnings.push(ValidationWarning {
+                warning_type: ValidationWarningType::PotentialIssue,
+                field_path: "storage.tiered.config.cloud_storage_credentials_source".to_string(),
+                message: format!(
+                    "Custom endpoint {} is used with the {} credentials source, which only works with cloud provider storage; set cloud_storage_credentials_source to config_file and provide an access and secret key",
+                    endpoint, credentials_source
+                ),
+            });
+        }
+    }
+
+    // Without a size or a dedicated volume the cache lives on, and can fill, the data volume
+    let cache_size_set = !is_blank(tiered_config.get("cloud_storage_cache_size"));
+    let cache_volume_set = path::get(config, "storage.tiered.persistentVolume.enabled").and_then(Value::as_bool) == Some(true)
+        || !is_blank(path::get(config, "storage.tiered.hostPath"));
+    if !cache_size_set && !cache_volume_set {
+        report.warnings.push(ValidationWarning {
+            warning_type: ValidationWarningType::SuboptimalConfiguration,
+            field_path: "storage.tiered.config.cloud_storage_cache_size".to_string(),
+            message: "Tiered storage is enabled without a cache size or cache volume, so the cache defaults onto the data volume and could fill it; set cloud_storage_cache_size (e.g. 20Gi) or enable storage.tiered.persistentVolume".to_string(),
+        });
+    }
+
+    // Writing without reading (or the reverse) leaves one half of tiered storage unused
+    let remote_write = tiered_config.get("cloud_storage_enable_remote_write").and_then(Value::as_bool);
+    let remote_read = tiered_config.get("cloud_storage_enable_remote_read").and_then(Value::as_bool);
+    if let (Some(write), Some(read)) = (remote_write, remote_read) {
+        if write != read {
+            let (field, message) = if write {
+                ("cloud_storage_enable_remote_read", "Remote write is enabled without remote read, so segments are uploaded to object storage but consumers can't read them back once they leave local disk; enable cloud_storage_enable_remote_read too")
+            } else {
+                ("cloud_storage_enable_remote_write", "Remote read is enabled without remote write, so nothing new is uploaded to object storage and only data written before can be read back; enable cloud_storage_enable_remote_write too")
+            };
+            report.warnings.push(ValidationWarning {
+                warning_type: ValidationWarningType::SuboptimalConfiguration,
+                field_path: format!("storage.tiered.config.{}", field),
+                message: message.to_string(),
+            });
+        }
+    }
+
+    report
+}
+
+fn is_blank(val: Option<&Value>) -> bool {
+    match val {
+        None | Some(Value::Null) => true,
+        Some(Value::String(s)) => s.trim().is_empty(),
+        _ => false,
+    }
+}
+
+// Where Redpanda publishes its images
+const OFFICIAL_IMAGE_REPOSITORIES: [&str; 3] = [
+    "docker.redpanda.com/redpandadata/redpanda",
+    "redpandadata/redpanda",
+    "docker.io/redpandadata/redpanda",
+];
+
+// Warn when "image.repository" isn't the official (or the expected) repository, since a mirror
+// has to carry the target tag before the upgrade can pull it
+pub fn validate_image_repository(config: &Value, expected: Option<&str>) -> Vec<ValidationWarning> {
+    let Some(repository) = path::get(config, "image.repository").and_then(Value::as_str).filter(|repository| !repository.trim().is_empty()) else {
+        return Vec::new();
+    };
+
+    let is_expected = match expected {
+        Some(expected) => repository == expected,
+        None => OFFICIAL_IMAGE_REPOSITORIES.contains(&repository),
+    };
+    if is_expected {
+        return Vec::new();
+    }
+
+    vec![ValidationWarning {
+        warning_type: ValidationWarningType::PotentialIssue,
+        field_path: "image.repository".to_string(),
+        message: format!(
+            "image.repository is {}, not {}; make sure it has the image tag you are upgrading to before upgrading",
+            repository,
+            expected.unwrap_or(OFFICIAL_IMAGE_REPOSITORIES[0])
+        ),
+    }]
+}
+
+// Redpanda's calendar versions ship three feature releases a year (24.1, 24.2, 24.3, 25.1, ...)
+const FEATURE_RELEASES_PER_YEAR: u64 = 3;
+
+// Position of a version in the sequence of feature releases, so 24.3 and 25.1 are one apart
+fn feature_release_ordinal(version: &SchemaVersion) -> u64 {
+    version.major * FEATURE_RELEASES_PER_YEAR + version.minor.clamp(1, FEATURE_RELEASES_PER_YEAR) - 1
+}
+
+// Warn when "image.tag" is more than one feature release behind the target app version.
+// Redpanda only supports upgrading one feature release at a time, and the last release of
+// a year is followed by the first release of the next one.
+pub fn validate_image_tag(config: &Value, target: &SchemaVersion) -> Vec<ValidationWarning> {
+    let mut warnings = Vec::new();
+    let Some(tag) = path::get(config, "image.tag").and_then(Value::as_str) else {
+        return warnings;
+    };
+    // An empty tag makes the chart deploy its own app version
+    if tag.is_empty() {
+        return warnings;
+    }
+
+    let current = match SchemaVersion::parse_lenient(tag) {
+        Ok(version) => version,
+        Err(e) => {
+            info!("Skipping image tag check: {}", e);
+            return warnings;
+        }
+    };
+
+    let too_far_behind = feature_release_ordinal(target) > feature_release_ordinal(&current) + 1;
+    if too_far_behind {
+        warnings.push(ValidationWarning {
+            warning_type: ValidationWarningType::SuboptimalConfiguration,
+            field_path: "image.tag".to_string(),
+            message: format!(
+                "Image tag {} is more than one feature release behind the chart's app version {}; upgrade Redpanda one feature release at a time",
+                tag, target
+            ),
+        });
+    }
+
+    warnings
+}
+
+// Warn about "developer_mode: true" anywhere in the config, it relaxes safety checks meant for production
+pub fn validate_developer_mode(config: &Value) -> Vec<ValidationWarning> {
+    let mut warnings = Vec::new();
+    for_each_entry(config, |field_path, key, val| {
+        if key.as_str() == Some("developer_mode") && val.as_bool() == Some(true) {
+            warnings.push(ValidationWarning {
+                warning_type: ValidationWarningType::PotentialIssue,
+                field_path: field_path.to_string(),
+                message: format!("{} is enabled; disable it before upgrading a production cluster", field_path),
+            });
+        }
+    });
+    warnings
+}
+
+// Call f with the dotted path, key and value of every mapping entry, in document order. Walks
+// with an explicit stack so deep configs can't overflow the call stack.
+fn for_each_entry<'a>(config: &'a Value, mut f: impl FnMut(&str, &'a Value, &'a Value)) {
+    let mut stack: Vec<(String, Option<&Value>, &Value)> = vec![(String::new(), None, config)];
+    while let Some((field_path, key, val)) = stack.pop() {
+        if let Some(key) = key {
+            f(&field_path, key, val);
+        }
+        let first_child = stack.len();
+        match val {
+            Value::Mapping(map) => stack.extend(map.iter().map(|(k, v)| (path::join(&field_path, k), Some(k), v))),
+            Value::Sequence(seq) => stack.extend(seq.iter().enumerate().map(|(i, v)| (format!("{}[{}]", field_path, i), None, v))),
+            _ => {}
+        }
+        // Reverse the children so the first one is popped first
+        stack[first_child..].reverse();
+    }
+}
+
+// Check every "schedule" string, e.g. of a CronJob block, is a valid five-field cron expression
+pub fn validate_cron_schedules(config: &Value) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    for_each_entry(config, |field_path, key, val| {
+        let (Some("schedule"), Value::String(schedule)) = (key.as_str(), val) else {
+            return;
+        };
+        if let Err(problem) = parse_cron(schedule) {
+            errors.push(ValidationError {
+                error_type: ValidationErrorType::InvalidFieldValue,
+                field_path: field_path.to_string(),
+                message: format!(
+                    "{:?} is not a valid cron schedule: {}; use five fields (minute hour day-of-month month day-of-week), e.g. \"0 2 * * *\" for 02:00 every day",
+                    schedule, problem
+                ),
+            });
+        }
+    });
+    errors
+}
+
+// Fields of a cron expression and the values each accepts
+const CRON_FIELDS: [(&str, u32, u32); 5] = [("minute", 0, 59), ("hour", 0, 23), ("day of month", 1, 31), ("month", 1, 12), ("day of week", 0, 7)];
+const CRON_MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+const CRON_DAYS: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+const CRON_MACROS: [&str; 8] = ["@yearly", "@annually", "@monthly", "@weekly", "@daily", "@midnight", "@hourly", "@every"];
+
+// Check a cron expression the way Kubernetes CronJobs read it, returning what's wrong with it
+fn parse_cron(schedule: &str) -> Result<(), String> {
+    let schedule = schedule.trim();
+    if schedule.starts_with('@') {
+        let is_macro = CRON_MACROS.iter().any(|name| schedule.eq_ignore_ascii_case(name) || (*name == "@every" && schedule.starts_with("@every ")));
+        return if is_macro { Ok(()) } else { Err(format!("unknown macro {}", schedule)) };
+    }
+
+    // A CronJob may start with its time zone
+    let fields: Vec<&str> = schedule.split_whitespace().skip_while(|field| field.starts_with("TZ=") || field.starts_with("CRON_TZ=")).collect();
+    if fields.len() != CRON_FIELDS.len() {
+        return Err(format!("expected 5 fields, found {}", fields.len()));
+    }
+
+    for (field, (name, min, max)) in fields.iter().zip(CRON_FIELDS) {
+        for item in field.split(',') {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => (range, Some(step)),
+                None => (item, None),
+            };
+            if step.is_some_and(|step| !step.parse::<u32>().is_ok_and(|step| step > 0)) {
+                return Err(format!("invalid step in {} field {:?}", name, field));
+            }
+            if range == "*" {
+                continue;
+            }
+
+            let value = |part: &str| {
+                let names: &[&str] = match name {
+                    "month" => &CRON_MONTHS,
+                    "day of week" => &CRON_DAYS,
+                    _ => &[],
+                };
+                let named = names.iter().position(|n| part.eq_ignore_ascii_case(n)).map(|i| i as u32 + if name == "month" { 1 } else { 0 });
+                named.or_else(|| part.parse::<u32>().ok()).filter(|value| (min..=max).contains(value))
+            };
+            let bounds = match range.split_once('-') {
+                Some((start, end)) => value(start).zip(value(end)).filter(|(start, end)| start <= end),
+                None => value(range).map(|value| (value, value)),
+            };
+            if bounds.is_none() {
+                return Err(format!("{} field {:?} must be within {}-{}", name, field, min, max));
+            }
+        }
+    }
+    Ok(())
+}
+
+// Check the mandatory Kafka listener survived the migration with a port
+pub fn validate_kafka_listener(config: &Value) -> Vec<ValidationError> {
+    if path::get(config, "listeners.kafka.port").is_some_and(|port| !port.is_null()) {
+        return Vec::new();
+    }
+
+    let field_path = if path::get(config, "listeners.kafka").is_some() { "listeners.kafka.port" } else { "listeners.kafka" };
+    vec![ValidationError {
+        error_type: ValidationErrorType::MissingRequiredField,
+        field_path: field_path.to_string(),
+        message: format!("{} is missing, but every deployment needs a Kafka listener; restore it, e.g. listeners.kafka.port: 9093", field_path),
+    }]
+}
+
+// Warn when the data directory is an emptyDir, which loses every broker's data when its pod restarts.
+// A hostPath keeps the data on the node, and an unset flag means the chart default (a persistent volume).
+pub fn validate_persistent_storage(config: &Value) -> Vec<ValidationWarning> {
+    let persistent = path::get(config, "storage.persistentVolume.enabled").and_then(Value::as_bool);
+    if persistent != Some(false) || !is_blank(path::get(config, "storage.hostPath")) {
+        return Vec::new();
+    }
+
+    vec![ValidationWarning {
+        warning_type: ValidationWarningType::PotentialIssue,
+        field_path: "storage.persistentVolume.enabled".to_string(),
+        message: "storage.persistentVolume.enabled is false and no storage.hostPath is set, so Redpanda stores its data in an emptyDir that is lost whenever a pod restarts, including during the upgrade; enable the persistent volume".to_string(),
+    }]
+}
+
+// Warn about an even broker count: a Raft majority of 4 brokers tolerates no more failures than 3
+pub fn validate_replica_parity(config: &Value) -> Vec<ValidationWarning> {
+    let mut warnings = Vec::new();
+    let Some(replicas) = path::get(config, "statefulset.replicas").and_then(Value::as_u64) else {
+        return warnings;
+    };
+
+    if replicas > 1 && replicas % 2 == 0 {
+        warnings.push(ValidationWarning {
+            warning_type: ValidationWarningType::SuboptimalConfiguration,
+            field_path: "statefulset.replicas".to_string(),
+            message: format!(
+                "statefulset.replicas is {}, an even broker count adds no fault tolerance over {}; use an odd count such as {} or {}",
+                replicas,
+                replicas - 1,
+                replicas - 1,
+                replicas + 1
+            ),
+        });
+    }
+
+    warnings
+}
+
+// Warn when rack awareness is enabled without a node label to read the rack from, or when the
+// brokers can't be spread evenly over the racks node affinity allows (skipped when that's unknown)
+pub fn validate_rack_awareness(config: &Value) -> Vec<ValidationWarning> {
+    let mut warnings = Vec::new();
+    if path::get(config, "rackAwareness.enabled").and_then(Value::as_bool) != Some(true) {
+        return warnings;
+    }
+
+    let Some(label) = path::get(config, "rackAwareness.nodeAnnotation").and_then(Value::as_str).filter(|label| !label.trim().is_empty()) else {
+        warnings.push(ValidationWarning {
+            warning_type: ValidationWarningType::PotentialIssue,
+            field_path: "rackAwareness.nodeAnnotation".to_string(),
+            message: "rackAwareness is enabled but no node label is set, so every broker ends up in the same rack; set it to a label such as topology.kubernetes.io/zone".to_string(),
+        });
+        return warnings;
+    };
+
+    // The rack count is only known when node affinity pins the brokers to a list of racks
+    let (Some(replicas), Some(racks)) = (path::get(config, "statefulset.replicas").and_then(Value::as_u64), rack_count(config, label)) else {
+        return warnings;
+    };
+    if racks > 1 && replicas % racks != 0 {
+        warnings.push(ValidationWarning {
+            warning_type: ValidationWarningType::SuboptimalConfiguration,
+            field_path: "statefulset.replicas".to_string(),
+            message: format!(
+                "statefulset.replicas is {} but node affinity spreads the brokers over {} racks ({}), so the racks hold uneven numbers of brokers; use a multiple of {}",
+                replicas, racks, label, racks
+            ),
+        });
+    }
+
+    warnings
+}
+
+// Number of racks the brokers may run in, from a node affinity "<label> In [...]" match expression
+fn rack_count(config: &Value, label: &str) -> Option<u64> {
+    // Search depth-first in document order, on an explicit stack so deep configs can't overflow the call stack
+    let mut stack = vec![config];
+    while let Some(val) = stack.pop() {
+        let first_child = stack.len();
+        match val {
+            Value::Mapping(map) => {
+                let is_rack_expression = map.get("key").and_then(Value::as_str) == Some(label) && map.get("operator").and_then(Value::as_str) == Some("In");
+                if let (true, Some(Value::Sequence(racks))) = (is_rack_expression, map.get("values")) {
+                    return Some(racks.len() as u64);
+                }
+                stack.extend(map.values());
+            }
+            Value::Sequence(seq) => stack.extend(seq),
+            _ => {}
+        }
+        stack[first_child..].reverse();
+    }
+    None
+}
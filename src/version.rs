@@ -0,0 +1,81 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SchemaVersion {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl SchemaVersion {
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        SchemaVersion { major, minor, patch }
+    }
+
+    // Parse a version that may omit the patch ("23.2") or minor and patch ("25"), defaulting them to 0
+    pub fn parse_lenient(s: &str) -> Result<Self, ParseVersionError> {
+        let parts = parse_parts(s)?;
+        match parts[..] {
+            [major] => Ok(SchemaVersion::new(major, 0, 0)),
+            [major, minor] => Ok(SchemaVersion::new(major, minor, 0)),
+            [major, minor, patch] => Ok(SchemaVersion::new(major, minor, patch)),
+            _ => Err(ParseVersionError(s.to_string())),
+        }
+    }
+
+    // The first release of the next minor version, e.g. 23.2.24 -> 23.3.0
+    pub fn next_minor(&self) -> Self {
+        SchemaVersion::new(self.major, self.minor + 1, 0)
+    }
+
+    // The first release of the next major version, e.g. 23.2.24 -> 24.0.0
+    pub fn next_major(&self) -> Self {
+        SchemaVersion::new(self.major + 1, 0, 0)
+    }
+}
+
+// The lowest of the available versions that is newer than `current`, the next step of a stepwise upgrade
+pub fn next_available_version(available: impl IntoIterator<Item = SchemaVersion>, current: &SchemaVersion) -> Option<SchemaVersion> {
+    available.into_iter().filter(|version| version > current).min()
+}
+
+impl fmt::Display for SchemaVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseVersionError(String);
+
+impl fmt::Display for ParseVersionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}' is not a MAJOR.MINOR.PATCH version", self.0)
+    }
+}
+
+impl std::error::Error for ParseVersionError {}
+
+// Parse a strict "MAJOR.MINOR.PATCH" version, with an optional leading "v" (e.g. "v23.2.24")
+impl FromStr for SchemaVersion {
+    type Err = ParseVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match parse_parts(s)?[..] {
+            [major, minor, patch] => Ok(SchemaVersion::new(major, minor, patch)),
+            _ => Err(ParseVersionError(s.to_string())),
+        }
+    }
+}
+
+// Split a dotted version (with an optional leading "v") into its numeric components
+fn parse_parts(s: &str) -> Result<Vec<u64>, ParseVersionError> {
+    let trimmed = s.trim();
+    let trimmed = trimmed.strip_prefix('v').unwrap_or(trimmed);
+
+    trimmed
+        .split('.')
+        .map(|part| part.parse::<u64>().map_err(|_| ParseVersionError(s.to_string())))
+        .collect()
+}
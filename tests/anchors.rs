@@ -0,0 +1,50 @@
+use redpanda_chart_upgrade::input;
+use serde_yaml::Value;
+
+#[test]
+fn anchors_and_merge_keys_are_expanded() {
+    let input = "
+resources: &resources
+  cpu:
+    cores: 4
+  memory:
+    container:
+      max: 8Gi
+statefulset:
+  sidecars:
+    resources: *resources
+console:
+  resources:
+    <<: *resources
+    cpu:
+      cores: 1
+";
+    let val = input::parse_values(input, "values.yaml").expect("Failed to parse the input");
+    let expected: Value = serde_yaml::from_str(
+        "
+resources:
+  cpu:
+    cores: 4
+  memory:
+    container:
+      max: 8Gi
+statefulset:
+  sidecars:
+    resources:
+      cpu:
+        cores: 4
+      memory:
+        container:
+          max: 8Gi
+console:
+  resources:
+    cpu:
+      cores: 1
+    memory:
+      container:
+        max: 8Gi
+",
+    )
+    .unwrap();
+    assert_eq!(val, expected);
+}
@@ -0,0 +1,49 @@
+use redpanda_chart_upgrade::legacy_migration::{self, MigrationOptions};
+use redpanda_chart_upgrade::validation::{self, ValidationOptions};
+use serde_yaml::Value;
+
+fn migrate_yaml(input: &str) -> Value {
+    let input: Value = serde_yaml::from_str(input).expect("Failed to parse the input");
+    let options = MigrationOptions { skip_merge: true, ..Default::default() };
+    legacy_migration::migrate_with_options(input, Value::Null, &options).0
+}
+
+#[test]
+fn string_flags_become_booleans() {
+    let migrated = migrate_yaml(
+        "
+storage:
+  tieredConfig:
+    cloud_storage_enabled: \"false\"
+tls:
+  enabled: \"Yes\"
+",
+    );
+    assert_eq!(migrated["storage"]["tiered"]["config"]["cloud_storage_enabled"], Value::Bool(false));
+    assert_eq!(migrated["tls"]["enabled"], Value::Bool(true));
+
+    // A disabled tiered storage config no longer needs a bucket or region
+    let report = validation::validate(&migrated, &ValidationOptions::default());
+    assert!(!report.has_errors(), "{:?}", report.errors);
+}
+
+#[test]
+fn string_flags_drive_the_enabled_checks() {
+    let migrated = migrate_yaml(
+        "
+monitoring:
+  enabled: \"false\"
+  scrapeInterval: 30s
+statefulset:
+  replicas: 3
+",
+    );
+    assert_eq!(migrated["monitoring"]["enabled"], Value::from(false));
+    assert!(migrated["monitoring"].get("scrapeInterval").is_none(), "{:?}", migrated);
+}
+
+#[test]
+fn other_strings_are_left_alone() {
+    let migrated = migrate_yaml("tls:\n  enabled: maybe\n");
+    assert_eq!(migrated["tls"]["enabled"], Value::from("maybe"));
+}
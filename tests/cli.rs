@@ -0,0 +1,311 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+// Run the binary in a scratch directory so nothing is written into the repo
+fn output(name: &str, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_redpanda-chart-upgrade"))
+        .args(args)
+        .current_dir(scratch_dir(name))
+        .env("RUST_LOG", "off")
+        .output()
+        .expect("Failed to run the binary")
+}
+
+fn run(name: &str, args: &[&str]) -> i32 {
+    output(name, args).status.code().expect("The binary was killed by a signal")
+}
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("redpanda-chart-upgrade-cli-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write_input(name: &str, contents: &str) -> String {
+    let path = scratch_dir(name).join("values.yaml");
+    fs::write(&path, contents).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+#[test]
+fn missing_input_file_exits_with_io_error() {
+    assert_eq!(run("missing", &["does-not-exist.yaml"]), 1);
+}
+
+#[test]
+fn malformed_yaml_exits_with_parse_error() {
+    let input = write_input("malformed", "statefulset: [unclosed\n");
+    assert_eq!(run("malformed", &[&input]), 2);
+}
+
+#[test]
+fn unknown_option_exits_with_parse_error() {
+    assert_eq!(run("unknown-option", &["--no-such-flag", "values.yaml"]), 2);
+}
+
+#[test]
+fn validation_errors_exit_with_validation_error() {
+    let input = write_input("invalid", "statefulset:\n  replicas: three\n");
+    assert_eq!(run("invalid", &["validate", &input]), 4);
+}
+
+#[test]
+fn valid_config_passes_validation() {
+    let input = write_input("valid", "statefulset:\n  replicas: 3\n");
+    assert_eq!(run("valid", &["validate", &input]), 0);
+}
+
+#[test]
+fn report_file_gets_the_report_instead_of_stdout() {
+    let input = write_input("report-file", "statefulset:\n  replicas: 4\n");
+    let report_file = scratch_dir("report-file").join("reports/validation.json");
+    let output = output("report-file", &["validate", "--report", "json", "--report-file", report_file.to_str().unwrap(), &input]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty(), "{}", String::from_utf8_lossy(&output.stdout));
+
+    let report: serde_json::Value = serde_json::from_str(&fs::read_to_string(&report_file).unwrap()).expect("The report should be valid JSON");
+    assert_eq!(report["warnings"][0]["field_path"], "statefulset.replicas");
+}
+
+#[test]
+fn timings_are_printed_to_stderr() {
+    let input = write_input("timings", "statefulset:\n  replicas: 3\n");
+    let output = output("timings", &["validate", "--timings", &input]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Timings:"), "{}", stderr);
+    assert!(stderr.contains(&format!("parse {}", input)), "{}", stderr);
+    assert!(stderr.contains("validate: "), "{}", stderr);
+}
+
+#[test]
+fn offline_migration_uses_the_bundled_chart() {
+    let input = write_input("offline", "statefulset:\n  replicas: 3\n");
+    // An unroutable proxy makes any fetch fail, so success means nothing was fetched
+    let output = Command::new(env!("CARGO_BIN_EXE_redpanda-chart-upgrade"))
+        .args(["--offline", "--fetch-retries", "0", &input])
+        .current_dir(scratch_dir("offline"))
+        .env("RUST_LOG", "off")
+        .env("HTTPS_PROXY", "http://127.0.0.1:9")
+        .output()
+        .expect("Failed to run the binary");
+    assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let updated: serde_yaml::Value = serde_yaml::from_str(&fs::read_to_string(scratch_dir("offline").join("updated-values.yaml")).unwrap()).unwrap();
+    assert_eq!(updated["statefulset"]["replicas"], 3);
+    assert_eq!(updated["tuning"]["tune_aio_events"], true);
+}
+
+#[test]
+fn preserve_null_sets_an_explicit_null() {
+    let input = write_input("preserve-null", "statefulset:\n  replicas: 3\n");
+    let priority_class = |name: &str, args: &[&str]| {
+        let args = [&["--offline", "--set", "statefulset.priorityClassName=null", &input], args].concat();
+        assert_eq!(run(name, &args), 0);
+        let updated: serde_yaml::Value = serde_yaml::from_str(&fs::read_to_string(scratch_dir(name).join("updated-values.yaml")).unwrap()).unwrap();
+        updated["statefulset"]["priorityClassName"].clone()
+    };
+
+    assert_eq!(priority_class("preserve-null-off", &[]), serde_yaml::Value::from("null"));
+    assert_eq!(priority_class("preserve-null-on", &["--preserve-null"]), serde_yaml::Value::Null);
+}
+
+#[test]
+fn post_check_runs_against_the_output_file() {
+    let input = write_input("post-check", "statefulset:\n  replicas: 3\n");
+    let output = output("post-check", &["--offline", "--post-check", "echo checking", &input]);
+    assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("checking updated-values.yaml"));
+}
+
+#[test]
+fn failing_post_check_exits_with_post_check_error() {
+    let input = write_input("post-check-failure", "statefulset:\n  replicas: 3\n");
+    assert_eq!(run("post-check-failure", &["--offline", "--post-check", "false", &input]), 5);
+}
+
+#[test]
+fn compact_output_leaves_out_chart_defaults() {
+    let input = write_input("compact", "statefulset:\n  replicas: 5\n  updateStrategy:\n    type: RollingUpdate\n");
+    assert_eq!(run("compact", &["--offline", "--compact", &input]), 0);
+
+    let updated = fs::read_to_string(scratch_dir("compact").join("updated-values.yaml")).unwrap();
+    let updated: serde_yaml::Value = serde_yaml::from_str(&updated).unwrap();
+    let expected: serde_yaml::Value = serde_yaml::from_str("statefulset:\n  replicas: 5\n").unwrap();
+    assert_eq!(updated, expected);
+}
+
+#[test]
+fn report_file_refuses_to_overwrite_an_input_without_force() {
+    let input = write_input("overwrite", "statefulset:\n  replicas: 3\n");
+    assert_eq!(run("overwrite", &["validate", "--report-file", &input, &input]), 2);
+    assert_eq!(fs::read_to_string(&input).unwrap(), "statefulset:\n  replicas: 3\n");
+
+    assert_eq!(run("overwrite", &["validate", "--force", "--report-file", &input, &input]), 0);
+    assert!(fs::read_to_string(&input).unwrap().contains("0 error(s)"));
+}
+
+#[test]
+fn overrides_file_wins_over_input_and_chart_defaults() {
+    let input = write_input("overrides", "statefulset:\n  replicas: 5\n");
+    let overrides = scratch_dir("overrides").join("production.yaml");
+    fs::write(&overrides, "statefulset:\n  replicas: 7\nstorage:\n  persistentVolume:\n    size: 100Gi\n").unwrap();
+    assert_eq!(run("overrides", &["--offline", "--overrides", overrides.to_str().unwrap(), &input]), 0);
+
+    let updated = fs::read_to_string(scratch_dir("overrides").join("updated-values.yaml")).unwrap();
+    let updated: serde_yaml::Value = serde_yaml::from_str(&updated).unwrap();
+    assert_eq!(updated["statefulset"]["replicas"], 7);
+    assert_eq!(updated["storage"]["persistentVolume"]["size"], "100Gi");
+    assert_eq!(updated["storage"]["persistentVolume"]["enabled"], true);
+}
+
+#[test]
+fn explain_removals_lists_only_keys_from_the_input() {
+    let input = write_input("explain-removals", "storage:\n  tieredStorageHostPath: /mnt/tiered\nstatefulset:\n  replicas: 3\n");
+    let output = output("explain-removals", &["--offline", "--explain-removals", &input]);
+    assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let removed: Vec<&str> = stdout.lines().skip_while(|line| *line != "Removed fields:").skip(1).collect();
+    // The bundled chart's empty defaults (nodeSelector: {}, tolerations: [], ...) were never in the input
+    assert_eq!(removed, ["  storage.tieredStorageHostPath: there is no storage.tiered block to move it into"], "{}", stdout);
+}
+
+#[test]
+fn provenance_header_hashes_the_input_file_as_written() {
+    let input = write_input("provenance", "statefulset:\n  replicas: ${REPLICAS}\n");
+    let output = Command::new(env!("CARGO_BIN_EXE_redpanda-chart-upgrade"))
+        .args(["--offline", "--provenance", "--expand-env", &input])
+        .current_dir(scratch_dir("provenance"))
+        .env("RUST_LOG", "off")
+        .env("REPLICAS", "5")
+        .output()
+        .expect("Failed to run the binary");
+    assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+
+    // sha256sum of the file before ${REPLICAS} was expanded
+    let updated = fs::read_to_string(scratch_dir("provenance").join("updated-values.yaml")).unwrap();
+    let expected = format!("# Input SHA-256: c75c946ebe54c5b826f597b6552a6648832da92c80b3d120a00a349c668855d6  {}", input);
+    assert!(updated.lines().any(|line| line == expected), "{}", updated);
+    assert!(updated.contains("\n  replicas: 5\n"), "{}", updated);
+}
+
+#[test]
+fn strict_mode_fails_on_warnings_alone() {
+    // An even broker count is a warning, not an error
+    let input = write_input("strict", "statefulset:\n  replicas: 4\n");
+    assert_eq!(run("strict", &["--offline", &input]), 0);
+    assert_eq!(run("strict", &["--offline", "--strict", &input]), 4);
+}
+
+#[test]
+fn unreachable_chart_registry_exits_with_network_error() {
+    // Bind and drop a listener to find a local port nothing is listening on
+    let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let input = write_input("network", "statefulset:\n  replicas: 3\n");
+    let reference = format!("oci://127.0.0.1:{}/redpanda:25.1.1", port);
+    let output = Command::new(env!("CARGO_BIN_EXE_redpanda-chart-upgrade"))
+        .args(["--oci", &reference, "--fetch-retries", "0", &input])
+        .current_dir(scratch_dir("network"))
+        .env("RUST_LOG", "off")
+        .env("XDG_CACHE_HOME", scratch_dir("network").join("cache"))
+        .output()
+        .expect("Failed to run the binary");
+    assert_eq!(output.status.code(), Some(3), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn target_version_selects_the_release_tag() {
+    let input = write_input("target-version", "statefulset:\n  replicas: 3\n");
+    let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let run_with_target = |extra: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_redpanda-chart-upgrade"))
+            .args(["--target-version", "5.9.0", "--fetch-retries", "0"])
+            .args(extra)
+            .arg(&input)
+            .current_dir(scratch_dir("target-version"))
+            .env("RUST_LOG", "warn")
+            .env("XDG_CACHE_HOME", scratch_dir("target-version").join("cache"))
+            .env("HTTPS_PROXY", format!("http://127.0.0.1:{}", port))
+            .output()
+            .expect("Failed to run the binary")
+    };
+
+    // The fetch goes to the chart's release tag instead of main, and fails on the closed proxy
+    let output = run_with_target(&[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(3), "{}", stderr);
+    assert!(stderr.contains("/redpanda-5.9.0/charts/redpanda/values.yaml"), "{}", stderr);
+
+    // The bundled chart has a fixed version, so the target version is ignored with a warning
+    let output = run_with_target(&["--offline"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr);
+    assert!(stderr.contains("--target-version is ignored with --offline"), "{}", stderr);
+
+    assert_eq!(run("target-version", &["--target-version", "latest", &input]), 2);
+}
+
+#[test]
+fn migration_lines_are_logged_to_stderr_and_filtered_by_rust_log() {
+    let input = write_input("logging", "license_key: abc\n");
+    let run_with_log = |rust_log: &str| {
+        Command::new(env!("CARGO_BIN_EXE_redpanda-chart-upgrade"))
+            .args(["--offline", &input])
+            .current_dir(scratch_dir("logging"))
+            .env("RUST_LOG", rust_log)
+            .output()
+            .expect("Failed to run the binary")
+    };
+
+    let output = run_with_log("info");
+    let (stdout, stderr) = (String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+    assert!(stderr.contains("✓ Migrating license_key -> enterprise.license"), "{}", stderr);
+    assert!(!stdout.contains('✓'), "{}", stdout);
+    assert!(stdout.contains("Merged config written to: updated-values"), "{}", stdout);
+
+    let output = run_with_log("warn");
+    assert!(!String::from_utf8_lossy(&output.stderr).contains('✓'));
+}
+
+#[test]
+fn quiet_mode_prints_nothing_for_a_clean_migration() {
+    let input = write_input("quiet", "statefulset:\n  replicas: 3\n");
+    for quiet_flag in ["--quiet", "-q"] {
+        // Without RUST_LOG the tool logs at info level, which quiet mode overrides
+        let output = Command::new(env!("CARGO_BIN_EXE_redpanda-chart-upgrade"))
+            .args(["--offline", quiet_flag, &input])
+            .current_dir(scratch_dir("quiet"))
+            .env_remove("RUST_LOG")
+            .output()
+            .expect("Failed to run the binary");
+        assert_eq!(output.status.code(), Some(0));
+        assert!(output.stdout.is_empty(), "{}", String::from_utf8_lossy(&output.stdout));
+        assert!(output.stderr.is_empty(), "{}", String::from_utf8_lossy(&output.stderr));
+    }
+    assert!(scratch_dir("quiet").join("updated-values.yaml").exists());
+}
+
+#[test]
+fn no_merge_output_only_holds_the_migrated_input() {
+    let input = write_input("no-merge", "statefulset:\n  replicas: 3\nstorage:\n  tieredConfig:\n    cloud_storage_enabled: false\n");
+    assert_eq!(run("no-merge", &["--offline", "--no-merge", &input]), 0);
+
+    let updated = fs::read_to_string(scratch_dir("no-merge").join("updated-values.yaml")).unwrap();
+    let updated: serde_yaml::Value = serde_yaml::from_str(&updated).unwrap();
+    let expected: serde_yaml::Value = serde_yaml::from_str("statefulset:\n  replicas: 3\nstorage:\n  tiered:\n    config:\n      cloud_storage_enabled: false\n").unwrap();
+    assert_eq!(updated, expected);
+}
+
+#[test]
+fn later_input_files_override_earlier_ones() {
+    let base = write_input("layered", "statefulset:\n  replicas: 3\n  budget:\n    maxUnavailable: 1\n");
+    let prod = scratch_dir("layered").join("prod.yaml");
+    fs::write(&prod, "statefulset:\n  replicas: 5\n").unwrap();
+    assert_eq!(run("layered", &["--offline", &base, prod.to_str().unwrap()]), 0);
+
+    let updated = fs::read_to_string(scratch_dir("layered").join("updated-values.yaml")).unwrap();
+    let updated: serde_yaml::Value = serde_yaml::from_str(&updated).unwrap();
+    assert_eq!(updated["statefulset"]["replicas"], 5);
+    assert_eq!(updated["statefulset"]["budget"]["maxUnavailable"], 1);
+}
@@ -0,0 +1,10 @@
+use redpanda_chart_upgrade::fetch;
+use redpanda_chart_upgrade::legacy_migration::{self, MigrationOptions, MigrationSummary};
+use serde_yaml::Value;
+
+// Migrate onto the bundled chart, the same way an --offline run does
+pub fn migrate_offline(input: &str) -> (Value, MigrationSummary) {
+    let input: Value = serde_yaml::from_str(input).unwrap();
+    let latest = fetch::parse_chart_values(fetch::BUNDLED_VALUES).unwrap();
+    legacy_migration::migrate_with_options(input, latest, &MigrationOptions::default())
+}
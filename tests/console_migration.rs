@@ -0,0 +1,76 @@
+use redpanda_chart_upgrade::fetch;
+use redpanda_chart_upgrade::legacy_migration::{self, MigrationSummary};
+use serde_yaml::Value;
+
+// A chart whose console subchart nests its settings under console.console
+const NESTED_CONSOLE_CHART: &str = "console:\n  enabled: true\n  console:\n    config: {}\n";
+
+fn migrate_console(input: &str, latest: &str) -> Value {
+    let mut val: Value = serde_yaml::from_str(input).expect("Failed to parse the input");
+    let latest: Value = serde_yaml::from_str(latest).expect("Failed to parse the chart values");
+    legacy_migration::migrate_console(&mut val, &latest, &mut MigrationSummary::default());
+    val
+}
+
+#[test]
+fn old_console_config_moves_under_console_console() {
+    let val = migrate_console(
+        "
+console:
+  enabled: true
+  config:
+    kafka:
+      brokers: [redpanda-0:9093]
+  ingress:
+    enabled: true
+    className: nginx
+  console:
+    config:
+      server:
+        listenPort: 8080
+",
+        NESTED_CONSOLE_CHART,
+    );
+    let expected: Value = serde_yaml::from_str(
+        "
+console:
+  enabled: true
+  ingress:
+    enabled: true
+    className: nginx
+  console:
+    config:
+      server:
+        listenPort: 8080
+      kafka:
+        brokers: [redpanda-0:9093]
+",
+    )
+    .unwrap();
+    assert_eq!(val, expected);
+}
+
+#[test]
+fn disabled_console_drops_stale_config() {
+    let val = migrate_console(
+        "
+console:
+  enabled: false
+  config:
+    kafka:
+      brokers: [redpanda-0:9093]
+  ingress:
+    enabled: true
+",
+        NESTED_CONSOLE_CHART,
+    );
+    let expected: Value = serde_yaml::from_str("console:\n  enabled: false\n  ingress:\n    enabled: true\n").unwrap();
+    assert_eq!(val, expected);
+}
+
+#[test]
+fn console_config_stays_in_place_for_charts_that_read_it_there() {
+    let input = "console:\n  enabled: true\n  config:\n    kafka:\n      brokers: [redpanda-0:9093]\n";
+    let val = migrate_console(input, fetch::BUNDLED_VALUES);
+    assert_eq!(val, serde_yaml::from_str::<Value>(input).unwrap());
+}
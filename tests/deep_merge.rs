@@ -0,0 +1,109 @@
+use redpanda_chart_upgrade::legacy_migration::{self, MigrationOptions};
+use redpanda_chart_upgrade::output::{self, OutputFormat};
+use redpanda_chart_upgrade::validation::{self, ValidationOptions};
+use redpanda_chart_upgrade::{diff, fetch, path};
+use serde_yaml::{Mapping, Value};
+
+const DEPTH: usize = 10_000;
+
+// Build `{a: {a: ... {<leaf_key>: 1}}}` nested `depth` levels deep without recursing
+fn nested(depth: usize, leaf_key: &str) -> Value {
+    let mut leaf = Mapping::new();
+    leaf.insert(Value::from(leaf_key), Value::from(1));
+    let mut val = Value::Mapping(leaf);
+    for _ in 0..depth {
+        let mut map = Mapping::new();
+        map.insert(Value::from("a"), val);
+        val = Value::Mapping(map);
+    }
+    val
+}
+
+// Tear the value down level by level, dropping it in one go would recurse just as deep
+fn dismantle(mut val: Value) {
+    while let Value::Mapping(mut map) = val {
+        val = map.remove("a").unwrap_or(Value::Null);
+    }
+}
+
+#[test]
+fn merge_handles_deeply_nested_mappings() {
+    let mut existing = nested(DEPTH, "existing");
+    let latest = nested(DEPTH, "latest");
+
+    legacy_migration::merge(&mut existing, &latest);
+
+    let mut leaf = &existing;
+    for _ in 0..DEPTH {
+        leaf = leaf.get("a").expect("nesting should be preserved");
+    }
+    assert_eq!(leaf.get("existing"), Some(&Value::from(1)));
+    assert_eq!(leaf.get("latest"), Some(&Value::from(1)));
+
+    dismantle(existing);
+    dismantle(latest);
+}
+
+// The innermost mapping of a value built by `nested`
+fn leaf_mut(val: &mut Value) -> &mut Mapping {
+    let mut leaf = val;
+    while leaf.get("a").is_some() {
+        leaf = leaf.get_mut("a").unwrap();
+    }
+    leaf.as_mapping_mut().unwrap()
+}
+
+#[test]
+fn prune_empty_handles_deeply_nested_mappings() {
+    let mut val = nested(DEPTH, "kept");
+    leaf_mut(&mut val).insert(Value::from("empty"), Value::Mapping(Mapping::new()));
+
+    let removed = legacy_migration::prune_empty(&mut val);
+    assert_eq!(removed.len(), 1);
+    assert!(removed[0].ends_with(".a.empty"));
+    assert_eq!(leaf_mut(&mut val).get("kept"), Some(&Value::from(1)));
+    assert!(leaf_mut(&mut val).get("empty").is_none());
+
+    dismantle(val);
+}
+
+#[test]
+fn validators_handle_deeply_nested_mappings() {
+    let mut val = nested(DEPTH, "developer_mode");
+    let leaf = leaf_mut(&mut val);
+    leaf.insert(Value::from("developer_mode"), Value::from(true));
+    leaf.insert(Value::from("schedule"), Value::from("every night"));
+
+    assert_eq!(validation::validate_developer_mode(&val).len(), 1);
+    assert_eq!(validation::validate_cron_schedules(&val).len(), 1);
+
+    dismantle(val);
+}
+
+// serde_yaml serializes, clones and compares values recursively itself, so the whole pipeline is run
+// less deep than the walkers above, but still deeper than any of them used to survive
+const PIPELINE_DEPTH: usize = 1_000;
+
+#[test]
+fn pipeline_handles_deeply_nested_input() {
+    let mut input: Value = serde_yaml::from_str("rackAwareness:\n  enabled: true\n  nodeAnnotation: topology.kubernetes.io/zone\nstatefulset:\n  replicas: 3\n").unwrap();
+    let mut deep = nested(PIPELINE_DEPTH, "developer_mode");
+    leaf_mut(&mut deep).insert(Value::from("developer_mode"), Value::from(true));
+    path::set(&mut input, "statefulset.podTemplate.spec.affinity.custom", deep);
+    let latest = fetch::parse_chart_values(fetch::BUNDLED_VALUES).unwrap();
+
+    let options = MigrationOptions { print_diffs: true, ..Default::default() };
+    let (mut migrated, _) = legacy_migration::migrate_with_options(input, latest, &options);
+    assert_eq!(migrated["statefulset"]["replicas"], Value::from(3));
+    assert!(path::get(&migrated, "statefulset.podTemplate.spec.affinity.podAntiAffinity").is_some());
+
+    let report = validation::validate(&migrated, &ValidationOptions::default());
+    assert!(report.warnings.iter().any(|warning| warning.field_path.ends_with(".a.developer_mode")), "{:?}", report.warnings);
+
+    output::sort_keys(&mut migrated);
+    assert!(diff::json_patch(&migrated, &migrated).is_empty());
+    let serialized = output::serialize(&migrated, OutputFormat::Yaml).unwrap();
+    assert!(serialized.lines().count() > PIPELINE_DEPTH);
+
+    dismantle(path::remove(&mut migrated, "statefulset.podTemplate.spec.affinity.custom").unwrap());
+}
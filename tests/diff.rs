@@ -0,0 +1,122 @@
+use redpanda_chart_upgrade::diff;
+use serde_yaml::Value;
+
+fn field_diff(old: &str, new: &str) -> (Vec<String>, Vec<String>, Vec<String>) {
+    let old: Value = serde_yaml::from_str(old).unwrap();
+    let new: Value = serde_yaml::from_str(new).unwrap();
+    diff::config_field_diff(&old, &new)
+}
+
+const OLD: &str = "
+statefulset:
+  replicas: 3
+  budget:
+    maxUnavailable: 1
+storage:
+  tieredConfig:
+    cloud_storage_enabled: true
+image:
+  tag: v23.2.24
+";
+
+#[test]
+fn added_paths_are_sorted() {
+    let (added, removed, changed) = field_diff(OLD, &format!("{}\nenterprise:\n  license: abc\ntuning:\n  tune_aio_events: true\n", OLD));
+    assert_eq!(added, ["enterprise.license", "tuning.tune_aio_events"]);
+    assert!(removed.is_empty());
+    assert!(changed.is_empty());
+}
+
+#[test]
+fn moved_keys_are_removed_and_added() {
+    let new = OLD.replace("tieredConfig:", "tiered:\n    config:").replace("    cloud_storage_enabled", "      cloud_storage_enabled");
+    let (added, removed, changed) = field_diff(OLD, &new);
+    assert_eq!(added, ["storage.tiered.config.cloud_storage_enabled"]);
+    assert_eq!(removed, ["storage.tieredConfig.cloud_storage_enabled"]);
+    assert!(changed.is_empty());
+}
+
+#[test]
+fn nested_changes_are_reported() {
+    let new = OLD.replace("maxUnavailable: 1", "maxUnavailable: 2").replace("v23.2.24", "v25.1.1");
+    let (added, removed, changed) = field_diff(OLD, &new);
+    assert!(added.is_empty());
+    assert!(removed.is_empty());
+    assert_eq!(changed, ["image.tag", "statefulset.budget.maxUnavailable"]);
+}
+
+#[test]
+fn overrides_keep_only_values_that_differ_from_defaults() {
+    let defaults: Value = serde_yaml::from_str(OLD).unwrap();
+    let merged: Value = serde_yaml::from_str("
+statefulset:
+  replicas: 5
+  budget:
+    maxUnavailable: 1
+storage:
+  tieredConfig:
+    cloud_storage_enabled: true
+image:
+  tag: v23.2.24
+service:
+  internal:
+    annotations:
+      example.com/team: data
+").unwrap();
+
+    let expected: Value = serde_yaml::from_str("
+statefulset:
+  replicas: 5
+service:
+  internal:
+    annotations:
+      example.com/team: data
+").unwrap();
+    assert_eq!(diff::overrides(&merged, &defaults), expected);
+}
+
+#[test]
+fn overrides_of_unchanged_config_are_empty() {
+    let defaults: Value = serde_yaml::from_str(OLD).unwrap();
+    assert_eq!(diff::overrides(&defaults, &defaults), Value::Mapping(Default::default()));
+}
+
+fn patch(before: &str, after: &str) -> Vec<diff::PatchOp> {
+    let before: Value = serde_yaml::from_str(before).unwrap();
+    let after: Value = serde_yaml::from_str(after).unwrap();
+    diff::json_patch(&before, &after)
+}
+
+#[test]
+fn renamed_field_becomes_a_move() {
+    let ops = patch(
+        "enterprise:\n  license_key: abc\n",
+        "enterprise:\n  license: abc\n",
+    );
+    assert_eq!(ops, [diff::PatchOp::Move { from: "/enterprise/license_key".to_string(), path: "/enterprise/license".to_string() }]);
+}
+
+#[test]
+fn patch_adds_removes_and_replaces() {
+    let ops = patch(
+        "statefulset:\n  replicas: 3\nmonitoring:\n  enabled: false\n",
+        "statefulset:\n  replicas: 5\ntuning:\n  tune_aio_events: true\n",
+    );
+    assert_eq!(
+        ops,
+        [
+            diff::PatchOp::Replace { path: "/statefulset/replicas".to_string(), value: Value::from(5) },
+            diff::PatchOp::Remove { path: "/monitoring".to_string() },
+            diff::PatchOp::Add { path: "/tuning".to_string(), value: serde_yaml::from_str("tune_aio_events: true").unwrap() },
+        ]
+    );
+
+    let json = serde_json::to_value(&ops[1]).unwrap();
+    assert_eq!(json, serde_json::json!({ "op": "remove", "path": "/monitoring" }));
+}
+
+#[test]
+fn pointer_tokens_are_escaped() {
+    let ops = patch("annotations: {}\n", "annotations:\n  example.com/team~a: data\n");
+    assert_eq!(ops, [diff::PatchOp::Add { path: "/annotations/example.com~1team~0a".to_string(), value: Value::from("data") }]);
+}
@@ -0,0 +1,21 @@
+use redpanda_chart_upgrade::input;
+
+fn lookup(name: &str) -> Option<String> {
+    match name {
+        "BUCKET" => Some("prod-tiered".to_string()),
+        "REGION" => Some("us-east-1".to_string()),
+        _ => None,
+    }
+}
+
+#[test]
+fn set_variables_are_substituted() {
+    let text = "bucket: ${BUCKET}\nregion: ${REGION}-${BUCKET}\n";
+    assert_eq!(input::expand_env_vars(text, lookup), "bucket: prod-tiered\nregion: us-east-1-prod-tiered\n");
+}
+
+#[test]
+fn unset_variables_and_non_placeholders_are_left_untouched() {
+    let text = "key: ${MISSING}\nliteral: ${not a name}\nprice: $5\nopen: ${BUCKET";
+    assert_eq!(input::expand_env_vars(text, lookup), text);
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use redpanda_chart_upgrade::validation;
+use serde_yaml::Value;
+
+#[test]
+fn enabled_external_access_keeps_its_type_and_domain() {
+    let (migrated, _) = common::migrate_offline(
+        "
+external:
+  enabled: true
+  type: LoadBalancer
+  domain: redpanda.example.com
+  advertisedPorts: [31092]
+listeners:
+  kafka:
+    port: 9093
+    external:
+      default:
+        port: 9094
+  admin:
+    port: 9644
+    external:
+      default:
+        port: 9645
+",
+    );
+
+    assert_eq!(migrated["external"]["enabled"], Value::from(true));
+    assert_eq!(migrated["external"]["type"], Value::from("LoadBalancer"));
+    assert_eq!(migrated["external"]["domain"], Value::from("redpanda.example.com"));
+    assert!(migrated["external"].get("advertisedPorts").is_none());
+
+    // The old advertisedPorts only applied to Kafka, the other listeners keep the chart's own ports
+    assert_eq!(migrated["listeners"]["kafka"]["external"]["default"]["advertisedPorts"], serde_yaml::from_str::<Value>("[31092]").unwrap());
+    assert_eq!(migrated["listeners"]["admin"]["external"]["default"]["advertisedPorts"], serde_yaml::from_str::<Value>("[31644]").unwrap());
+    assert!(migrated["listeners"]["kafka"]["external"]["default"].get("type").is_none());
+    let errors = validation::validate_listener_ports(&migrated);
+    assert!(errors.is_empty(), "{:?}", errors);
+}
+
+#[test]
+fn advertised_ports_without_a_kafka_listener_create_one() {
+    let (migrated, _) = common::migrate_offline("external:\n  enabled: true\n  advertisedPorts: [31092]\n");
+
+    assert_eq!(migrated["listeners"]["kafka"]["external"]["default"]["advertisedPorts"], serde_yaml::from_str::<Value>("[31092]").unwrap());
+    assert_eq!(migrated["listeners"]["kafka"]["external"]["default"]["port"], Value::from(9094));
+    assert_eq!(migrated["listeners"]["http"]["external"]["default"]["advertisedPorts"], serde_yaml::from_str::<Value>("[30082]").unwrap());
+    assert!(validation::validate_listener_ports(&migrated).is_empty());
+}
+
+#[test]
+fn disabled_external_access_stays_disabled() {
+    let (migrated, _) = common::migrate_offline("external:\n  enabled: false\n  type: LoadBalancer\n");
+
+    assert_eq!(migrated["external"]["enabled"], Value::from(false));
+    assert_eq!(migrated["external"]["type"], Value::from("LoadBalancer"));
+}
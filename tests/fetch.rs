@@ -0,0 +1,145 @@
+use redpanda_chart_upgrade::fetch::{self, CachingFetcher, ChartFetcher, FetchError, RetryingFetcher, StaticFetcher};
+use redpanda_chart_upgrade::legacy_migration::MigrationOptions;
+use serde_yaml::Value;
+use std::fs;
+use std::future::Future;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn fixture_file(name: &str) -> String {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/5.0.10-to-25.1");
+    fs::read_to_string(fixture.join(name)).unwrap()
+}
+
+#[tokio::test]
+async fn pipeline_runs_against_a_static_chart() {
+    let fetcher = StaticFetcher {
+        values: fixture_file("latest.yaml"),
+        chart_metadata: Some("name: redpanda\nversion: 5.10.1\nappVersion: v25.1.1\n".to_string()),
+    };
+    let input: Value = serde_yaml::from_str(&fixture_file("input.yaml")).unwrap();
+
+    let (migrated, _) = fetch::migrate_onto_chart(&fetcher, input, &MigrationOptions::default()).await.unwrap();
+    assert_eq!(serde_yaml::to_string(&migrated).unwrap(), fixture_file("expected.yaml"));
+
+    let chart = fetch::chart_metadata(&fetcher).await.expect("Chart.yaml should parse");
+    assert_eq!(chart["appVersion"], Value::from("v25.1.1"));
+}
+
+#[tokio::test]
+async fn unparseable_chart_values_are_an_error() {
+    let fetcher = StaticFetcher { values: "image: [unclosed".to_string(), chart_metadata: None };
+
+    let result = fetch::migrate_onto_chart(&fetcher, Value::Null, &MigrationOptions::default()).await;
+    assert!(result.is_err());
+    assert!(fetch::chart_metadata(&fetcher).await.is_none());
+}
+
+// Fails the first `failures` fetches with the given error, then serves the values
+struct FlakyFetcher {
+    failures: usize,
+    error: FetchError,
+    attempts: AtomicUsize,
+}
+
+impl ChartFetcher for FlakyFetcher {
+    fn fetch_values(&self) -> impl Future<Output = Result<String, FetchError>> + Send {
+        let attempt = self.attempts.fetch_add(1, Ordering::SeqCst);
+        let result = if attempt < self.failures { Err(self.error.clone()) } else { Ok("image: {}\nstatefulset:\n  replicas: 3\nstorage: {}\n".to_string()) };
+        async move { result }
+    }
+
+    async fn fetch_chart_metadata(&self) -> Result<String, FetchError> {
+        Err(FetchError::Status("No Chart.yaml configured".to_string()))
+    }
+}
+
+fn flaky(failures: usize, error: FetchError) -> RetryingFetcher<FlakyFetcher> {
+    let fetcher = FlakyFetcher { failures, error, attempts: AtomicUsize::new(0) };
+    RetryingFetcher::new(fetcher, 3, Duration::from_millis(1))
+}
+
+#[tokio::test]
+async fn network_errors_are_retried() {
+    let fetcher = flaky(2, FetchError::Network("connection reset".to_string()));
+    let input = Value::Mapping(Default::default());
+
+    let (migrated, _) = fetch::migrate_onto_chart(&fetcher, input, &MigrationOptions::default()).await.unwrap();
+    assert_eq!(migrated["statefulset"]["replicas"], Value::from(3));
+}
+
+#[tokio::test]
+async fn retries_give_up_after_the_limit() {
+    let fetcher = flaky(4, FetchError::Network("connection reset".to_string()));
+
+    let result = fetch::migrate_onto_chart(&fetcher, Value::Null, &MigrationOptions::default()).await;
+    assert_eq!(result.unwrap_err(), FetchError::Network("connection reset".to_string()));
+}
+
+#[tokio::test]
+async fn error_statuses_are_not_retried() {
+    let fetcher = flaky(1, FetchError::Status("404 Not Found".to_string()));
+
+    let result = fetch::migrate_onto_chart(&fetcher, Value::Null, &MigrationOptions::default()).await;
+    assert_eq!(result.unwrap_err(), FetchError::Status("404 Not Found".to_string()));
+}
+
+#[test]
+fn html_instead_of_chart_values_is_rejected() {
+    let page = "<!DOCTYPE html>\n<html><head><title>Rate limit exceeded</title></head><body>Try again later</body></html>\n";
+    let error = fetch::parse_chart_values(page).unwrap_err();
+    assert!(matches!(error, FetchError::Parse(_)));
+    assert!(error.to_string().contains("doesn't look like redpanda chart values"), "{}", error);
+
+    assert!(fetch::parse_chart_values("image: {}\nstatefulset: {}\n").is_err());
+    assert!(fetch::parse_chart_values(&fixture_file("latest.yaml")).is_ok());
+}
+
+// Counts the fetches that reach it, standing in for the network
+struct CountingFetcher {
+    fetches: Arc<AtomicUsize>,
+}
+
+impl ChartFetcher for CountingFetcher {
+    fn fetch_values(&self) -> impl Future<Output = Result<String, FetchError>> + Send {
+        self.fetches.fetch_add(1, Ordering::SeqCst);
+        async { Ok("image: {}\nstatefulset:\n  replicas: 3\nstorage: {}\n".to_string()) }
+    }
+
+    async fn fetch_chart_metadata(&self) -> Result<String, FetchError> {
+        Err(FetchError::Status("No Chart.yaml configured".to_string()))
+    }
+}
+
+// Fetch the values once through a cache in cache_dir, returning how many fetches reached the network
+async fn fetches_through_cache(cache_dir: &Path, source: &str, ttl: Duration) -> usize {
+    let fetches = Arc::new(AtomicUsize::new(0));
+    let fetcher = CachingFetcher::new(CountingFetcher { fetches: fetches.clone() }, cache_dir, source, ttl);
+    assert!(fetch::parse_chart_values(&fetcher.fetch_values().await.unwrap()).is_ok());
+    fetches.load(Ordering::SeqCst)
+}
+
+#[tokio::test]
+async fn cached_chart_values_are_read_from_disk() {
+    let cache_dir = std::env::temp_dir().join(format!("chart-cache-test-{}", std::process::id()));
+    let source = "https://example.com/charts/redpanda/";
+    let ttl = Duration::from_secs(3600);
+
+    assert_eq!(fetches_through_cache(&cache_dir, source, ttl).await, 1);
+    // A second run reuses the download instead of going to the network
+    assert_eq!(fetches_through_cache(&cache_dir, source, ttl).await, 0);
+    // A zero ttl, as with --no-cache, always fetches
+    assert_eq!(fetches_through_cache(&cache_dir, source, Duration::ZERO).await, 1);
+    // Another chart has its own cache entry
+    assert_eq!(fetches_through_cache(&cache_dir, "oci://example.com/redpanda:25.1.1", ttl).await, 1);
+
+    fs::remove_dir_all(&cache_dir).unwrap();
+}
+
+#[test]
+fn bundled_chart_version_comes_from_the_bundled_chart_yaml() {
+    let chart: Value = serde_yaml::from_str(fetch::BUNDLED_CHART_METADATA).unwrap();
+    assert_eq!(chart["version"].as_str(), Some(fetch::BUNDLED_CHART_VERSION));
+}
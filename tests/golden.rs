@@ -0,0 +1,50 @@
+use redpanda_chart_upgrade::legacy_migration;
+use serde_yaml::Value;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+// Each directory under tests/fixtures holds an input.yaml, the latest.yaml chart values it is
+// migrated onto, and the expected.yaml golden file. Run with UPDATE_GOLDEN=1 to regenerate.
+#[test]
+fn migrations_match_golden_files() {
+    let fixtures_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let mut fixtures: Vec<_> = fs::read_dir(&fixtures_dir)
+        .expect("Failed to read the fixtures directory")
+        .map(|entry| entry.expect("Failed to read a fixture").path())
+        .filter(|path| path.is_dir())
+        .collect();
+    fixtures.sort();
+    assert!(!fixtures.is_empty(), "No fixtures found in {}", fixtures_dir.display());
+
+    for fixture in fixtures {
+        check_fixture(&fixture);
+    }
+}
+
+fn check_fixture(fixture: &Path) {
+    let input = load_yaml(&fixture.join("input.yaml"));
+    let latest = load_yaml(&fixture.join("latest.yaml"));
+    let actual = serde_yaml::to_string(&legacy_migration::migrate(input, latest)).expect("Failed to serialize the migrated config");
+
+    let golden_path = fixture.join("expected.yaml");
+    if env::var("UPDATE_GOLDEN").as_deref() == Ok("1") {
+        fs::write(&golden_path, &actual).expect("Failed to write the golden file");
+        return;
+    }
+
+    let expected = fs::read_to_string(&golden_path)
+        .unwrap_or_else(|_| panic!("Missing golden file {}, run with UPDATE_GOLDEN=1 to create it", golden_path.display()));
+    assert!(
+        actual == expected,
+        "Migration of {} no longer matches {}, run with UPDATE_GOLDEN=1 if the change is intended\n\n{}",
+        fixture.display(),
+        golden_path.display(),
+        actual
+    );
+}
+
+fn load_yaml(path: &Path) -> Value {
+    let contents = fs::read_to_string(path).unwrap_or_else(|_| panic!("Failed to read {}", path.display()));
+    serde_yaml::from_str(&contents).unwrap_or_else(|e| panic!("Failed to parse {}: {}", path.display(), e))
+}
@@ -0,0 +1,21 @@
+use redpanda_chart_upgrade::{input, legacy_migration, output};
+use serde_yaml::Value;
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn gzipped_input_migrates_like_the_uncompressed_file() {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/5.0.10-to-25.1");
+    let contents = fs::read_to_string(fixture.join("input.yaml")).unwrap();
+    let latest: Value = serde_yaml::from_str(&fs::read_to_string(fixture.join("latest.yaml")).unwrap()).unwrap();
+    let gzipped = output::gzip(&contents).unwrap();
+
+    for file_name in ["input.yaml.gz", "input.yaml"] {
+        let decoded = input::decode_values(&gzipped, file_name).expect("Failed to decompress the input");
+        assert_eq!(decoded, contents);
+
+        let migrated = legacy_migration::migrate(input::parse_values(&decoded, file_name).unwrap(), latest.clone());
+        let expected = legacy_migration::migrate(input::parse_values(&contents, "input.yaml").unwrap(), latest.clone());
+        assert_eq!(migrated, expected);
+    }
+}
@@ -0,0 +1,28 @@
+use redpanda_chart_upgrade::input;
+use redpanda_chart_upgrade::legacy_migration;
+use redpanda_chart_upgrade::output::{self, OutputFormat};
+use serde_yaml::Value;
+
+const YAML_INPUT: &str = "license_key: abc\nstatefulset:\n  replicas: 5\n  nodeSelector:\n    zone: a\n";
+const JSON_INPUT: &str = r#"{"license_key": "abc", "statefulset": {"replicas": 5, "nodeSelector": {"zone": "a"}}}"#;
+
+#[test]
+fn json_input_migrates_like_the_same_yaml() {
+    let latest: Value = serde_yaml::from_str("enterprise:\n  license: ''\nstatefulset:\n  replicas: 3\n").unwrap();
+
+    // Detected by the extension, and by the leading '{' whatever the file is called
+    for file_name in ["values.json", "values.txt"] {
+        let json = input::parse_values(JSON_INPUT, file_name).unwrap();
+        let yaml = input::parse_values(YAML_INPUT, "values.yaml").unwrap();
+        assert_eq!(legacy_migration::migrate(json, latest.clone()), legacy_migration::migrate(yaml, latest.clone()), "{}", file_name);
+    }
+}
+
+#[test]
+fn json_output_parses_back_to_the_same_config() {
+    let val = input::parse_values(YAML_INPUT, "values.yaml").unwrap();
+    let serialized = output::serialize(&val, OutputFormat::Json).unwrap();
+
+    assert!(serialized.starts_with('{'), "{}", serialized);
+    assert_eq!(input::parse_values(&serialized, "updated-values.json").unwrap(), val);
+}
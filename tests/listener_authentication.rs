@@ -0,0 +1,64 @@
+use redpanda_chart_upgrade::legacy_migration::{self, MigrationSummary};
+use redpanda_chart_upgrade::path;
+use serde_yaml::Value;
+
+fn migrate(input: &str) -> Value {
+    let mut val: Value = serde_yaml::from_str(input).expect("Failed to parse the input");
+    legacy_migration::migrate_listener_authentication(&mut val, &mut MigrationSummary::default());
+    val
+}
+
+fn method<'a>(val: &'a Value, listener_path: &str) -> Option<&'a str> {
+    path::get(val, &format!("{}.authenticationMethod", listener_path)).and_then(Value::as_str)
+}
+
+#[test]
+fn global_sasl_sets_each_listener_method() {
+    let val = migrate(
+        "
+auth:
+  sasl:
+    enabled: true
+listeners:
+  kafka:
+    port: 9093
+    authenticationMethod: null
+    external:
+      default:
+        port: 9094
+  http:
+    port: 8082
+  schemaRegistry:
+    port: 8081
+",
+    );
+    assert_eq!(method(&val, "listeners.kafka"), Some("sasl"));
+    assert_eq!(method(&val, "listeners.kafka.external.default"), Some("sasl"));
+    assert_eq!(method(&val, "listeners.http"), Some("http_basic"));
+    assert_eq!(method(&val, "listeners.schemaRegistry"), Some("http_basic"));
+}
+
+#[test]
+fn explicit_methods_and_disabled_listeners_are_left_alone() {
+    let val = migrate(
+        "
+auth:
+  sasl:
+    enabled: true
+listeners:
+  kafka:
+    authenticationMethod: mtls_identity
+  http:
+    enabled: false
+",
+    );
+    assert_eq!(method(&val, "listeners.kafka"), Some("mtls_identity"));
+    assert_eq!(method(&val, "listeners.http"), None);
+    assert_eq!(method(&val, "listeners.schemaRegistry"), Some("http_basic"));
+}
+
+#[test]
+fn listeners_are_untouched_without_sasl() {
+    let val = migrate("auth:\n  sasl:\n    enabled: false\nlisteners:\n  kafka:\n    port: 9093\n");
+    assert_eq!(method(&val, "listeners.kafka"), None);
+}
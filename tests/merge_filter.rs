@@ -0,0 +1,83 @@
+use redpanda_chart_upgrade::legacy_migration::{self, MergeStrategy, MigrationOptions};
+use serde_yaml::Value;
+
+const INPUT: &str = "
+statefulset:
+  replicas: 5
+";
+
+const LATEST: &str = "
+statefulset:
+  replicas: 3
+  budget:
+    maxUnavailable: 1
+connectors:
+  enabled: false
+tests:
+  enabled: true
+console:
+  enabled: true
+";
+
+fn migrate_with(options: MigrationOptions) -> Value {
+    let input: Value = serde_yaml::from_str(INPUT).unwrap();
+    let latest: Value = serde_yaml::from_str(LATEST).unwrap();
+    legacy_migration::migrate_with_options(input, latest, &options).0
+}
+
+#[test]
+fn excluded_subtrees_are_not_merged() {
+    let merged = migrate_with(MigrationOptions {
+        merge_exclude: vec!["connectors".to_string(), "tests".to_string(), "statefulset.budget".to_string()],
+        ..Default::default()
+    });
+    let expected: Value = serde_yaml::from_str(
+        "
+statefulset:
+  replicas: 5
+console:
+  enabled: true
+",
+    )
+    .unwrap();
+    assert_eq!(merged, expected);
+}
+
+#[test]
+fn include_only_limits_the_merged_keys() {
+    let merged = migrate_with(MigrationOptions {
+        merge_include_only: vec!["statefulset".to_string(), "console.enabled".to_string()],
+        ..Default::default()
+    });
+    let expected: Value = serde_yaml::from_str(
+        "
+statefulset:
+  replicas: 5
+  budget:
+    maxUnavailable: 1
+console:
+  enabled: true
+",
+    )
+    .unwrap();
+    assert_eq!(merged, expected);
+}
+
+#[test]
+fn add_new_only_leaves_existing_mappings_as_they_are() {
+    let merged = migrate_with(MigrationOptions { merge_strategy: MergeStrategy::AddNewOnly, ..Default::default() });
+    let expected: Value = serde_yaml::from_str(
+        "
+statefulset:
+  replicas: 5
+connectors:
+  enabled: false
+tests:
+  enabled: true
+console:
+  enabled: true
+",
+    )
+    .unwrap();
+    assert_eq!(merged, expected);
+}
@@ -0,0 +1,30 @@
+mod common;
+
+use serde_yaml::Value;
+
+#[test]
+fn enabled_monitoring_keeps_its_labels() {
+    let (migrated, _) = common::migrate_offline("monitoring:\n  enabled: true\n  scrapeInterval: 15s\n  labels:\n    release: prometheus\n");
+
+    assert_eq!(migrated["monitoring"]["enabled"], Value::from(true));
+    assert_eq!(migrated["monitoring"]["scrapeInterval"], Value::from("15s"));
+    assert_eq!(migrated["monitoring"]["labels"]["release"], Value::from("prometheus"));
+    assert!(migrated.get("serviceMonitor").is_none());
+}
+
+#[test]
+fn monitoring_without_an_enabled_flag_keeps_its_labels() {
+    let (migrated, _) = common::migrate_offline("monitoring:\n  labels:\n    release: prometheus\n");
+
+    assert_eq!(migrated["monitoring"]["labels"]["release"], Value::from("prometheus"));
+    assert_eq!(migrated["monitoring"]["enabled"], Value::from(false));
+}
+
+#[test]
+fn disabled_monitoring_drops_its_stale_settings() {
+    let (migrated, summary) = common::migrate_offline("monitoring:\n  enabled: false\n  labels:\n    release: prometheus\n");
+
+    assert_eq!(migrated["monitoring"]["enabled"], Value::from(false));
+    assert!(migrated["monitoring"].get("labels").is_none());
+    assert!(summary.removed_fields.iter().any(|removed| removed.field_path == "monitoring.labels" && removed.reason == "monitoring is disabled"));
+}
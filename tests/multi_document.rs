@@ -0,0 +1,36 @@
+use redpanda_chart_upgrade::input;
+use redpanda_chart_upgrade::legacy_migration;
+use redpanda_chart_upgrade::output::{self, OutputFormat};
+use serde_yaml::Value;
+
+const INPUT: &str = "
+storage:
+  tieredConfig:
+    cloud_storage_enabled: true
+---
+license_key: abc
+";
+
+#[test]
+fn every_document_is_migrated_and_re_separated() {
+    let documents = input::parse_documents(INPUT, "values.yaml").unwrap();
+    assert_eq!(documents.len(), 2);
+
+    let latest = Value::Mapping(Default::default());
+    let migrated: Vec<Value> = documents.into_iter().map(|document| legacy_migration::migrate(document, latest.clone())).collect();
+    let serialized = output::serialize_documents(&migrated, OutputFormat::Yaml).unwrap();
+    assert_eq!(serialized, "storage:\n  tiered:\n    config:\n      cloud_storage_enabled: true\n---\nenterprise:\n  license: abc\n");
+}
+
+#[test]
+fn single_document_files_parse_to_one_document() {
+    let documents = input::parse_documents("---\nstatefulset:\n  replicas: 3\n", "values.yaml").unwrap();
+    assert_eq!(documents.len(), 1);
+    assert!(input::parse_values(INPUT, "values.yaml").is_err());
+}
+
+#[test]
+fn several_documents_cannot_be_written_as_json() {
+    let documents = [Value::Mapping(Default::default()), Value::Mapping(Default::default())];
+    assert!(output::serialize_documents(&documents, OutputFormat::Json).is_err());
+}
@@ -0,0 +1,71 @@
+use redpanda_chart_upgrade::input::SetOverride;
+use redpanda_chart_upgrade::legacy_migration::{self, MergeStrategy, MigrationOptions};
+use serde_yaml::Value;
+
+const INPUT: &str = "statefulset:\n  priorityClassName: null\n  replicas: 3\nnameOverride: null\nstorage:\n  persistentVolume:\n    storageClass: null\ntls:\n  enabled: true\n";
+const LAYER: &str = "tls:\n  enabled: null\nlisteners:\n  kafka:\n    port: null\n";
+const LATEST: &str = "nameOverride: \"\"\nstatefulset:\n  priorityClassName: system-cluster-critical\n  replicas: 1\n  nodeSelector: {}\ntls:\n  enabled: true\nlisteners:\n  kafka:\n    port: 9093\nconfig:\n  cluster:\n    default_topic_replications: null\n";
+
+// What the migration wrote for INPUT layered with LAYER before --preserve-null existed, with either merge strategy
+const UNFLAGGED_OUTPUT: &str = "tls:
+  enabled: null
+listeners:
+  kafka:
+    port: null
+statefulset:
+  priorityClassName: null
+  replicas: 3
+nameOverride: null
+storage:
+  persistentVolume:
+    storageClass: ''
+config:
+  cluster:
+    default_topic_replications: null
+";
+
+fn migrate_layers(merge_strategy: MergeStrategy) -> String {
+    let layers = [INPUT, LAYER].map(|layer| serde_yaml::from_str::<Value>(layer).unwrap());
+    let input = legacy_migration::merge_layers(layers);
+    let latest = serde_yaml::from_str(LATEST).unwrap();
+    let (migrated, _) = legacy_migration::migrate_with_options(input, latest, &MigrationOptions { merge_strategy, ..Default::default() });
+    serde_yaml::to_string(&migrated).unwrap()
+}
+
+#[test]
+fn unflagged_output_is_unchanged_by_preserve_null() {
+    assert_eq!(migrate_layers(MergeStrategy::KeepExisting), UNFLAGGED_OUTPUT);
+    assert_eq!(migrate_layers(MergeStrategy::AddNewOnly), UNFLAGGED_OUTPUT);
+}
+
+#[test]
+fn explicit_nulls_survive_the_merge_and_prune() {
+    let input: Value = serde_yaml::from_str("statefulset:\n  priorityClassName: null\n  replicas: 3\nnameOverride: null\n").unwrap();
+    let latest: Value = serde_yaml::from_str("statefulset:\n  priorityClassName: system-cluster-critical\n  replicas: 1\n").unwrap();
+    let (migrated, summary) = legacy_migration::migrate_with_options(input, latest, &MigrationOptions::default());
+
+    // A null blanks out the chart default in Helm, so it isn't filled in or pruned
+    assert_eq!(migrated["statefulset"]["priorityClassName"], Value::Null);
+    assert!(migrated.as_mapping().unwrap().contains_key("nameOverride"));
+    assert_eq!(migrated["statefulset"]["replicas"], Value::from(3));
+    assert_eq!(summary.added, 0);
+}
+
+#[test]
+fn later_layers_can_null_out_a_value() {
+    let base: Value = serde_yaml::from_str("tls:\n  enabled: true\n").unwrap();
+    let layer: Value = serde_yaml::from_str("tls:\n  enabled: null\n").unwrap();
+
+    assert_eq!(legacy_migration::merge_layers([base, layer])["tls"]["enabled"], Value::Null);
+}
+
+#[test]
+fn set_overrides_remember_a_null_value() {
+    let set_override: SetOverride = "statefulset.priorityClassName=null".parse().unwrap();
+    assert_eq!(set_override.value, Value::from("null"));
+    assert!(set_override.null);
+
+    assert!("statefulset.priorityClassName=~".parse::<SetOverride>().unwrap().null);
+    assert!(!"statefulset.priorityClassName=".parse::<SetOverride>().unwrap().null);
+    assert!(!"statefulset.priorityClassName='null'".parse::<SetOverride>().unwrap().null);
+}
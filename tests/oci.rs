@@ -0,0 +1,92 @@
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use redpanda_chart_upgrade::fetch::{ChartFetcher, FetchError, OciChartFetcher};
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+const VALUES: &str = "statefulset:\n  replicas: 3\n";
+const CHART: &str = "name: redpanda\nversion: 25.1.1\nappVersion: v25.1.1\n";
+
+// A gzipped chart archive with a subchart whose values.yaml must not be picked up
+fn chart_archive() -> Vec<u8> {
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    for (path, contents) in [
+        ("redpanda/charts/console/values.yaml", "console: {}\n"),
+        ("redpanda/Chart.yaml", CHART),
+        ("redpanda/values.yaml", VALUES),
+    ] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, path, contents.as_bytes()).unwrap();
+    }
+    builder.into_inner().unwrap().finish().unwrap()
+}
+
+// Serve a chart the way a registry does, requiring an anonymous bearer token for the manifest.
+// The challenge's scope has a comma inside its quotes, as registries send for push access.
+fn start_registry() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let archive = chart_archive();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            respond(stream.unwrap(), port, &archive);
+        }
+    });
+    port
+}
+
+fn respond(mut stream: TcpStream, port: u16, archive: &[u8]) {
+    let mut request = Vec::new();
+    let mut buf = [0; 1024];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = stream.read(&mut buf).unwrap();
+        if read == 0 {
+            return;
+        }
+        request.extend_from_slice(&buf[..read]);
+    }
+    let request = String::from_utf8_lossy(&request).to_lowercase();
+    let target = request.split_whitespace().nth(1).unwrap_or_default().to_string();
+
+    let manifest = r#"{"schemaVersion":2,"layers":[{"mediaType":"application/vnd.cncf.helm.chart.content.v1.tar+gzip","digest":"sha256:chart"}]}"#;
+    let challenge = format!("WWW-Authenticate: Bearer realm=\"http://127.0.0.1:{}/token\", service=\"registry\",scope=\"repository:charts/redpanda:pull,push\"\r\n", port);
+    let (status, headers, body): (&str, String, Vec<u8>) = match target.as_str() {
+        "/token?service=registry&scope=repository:charts/redpanda:pull,push" => ("200 OK", String::new(), br#"{"token":"secret"}"#.to_vec()),
+        "/v2/charts/redpanda/manifests/25.1.1" if request.contains("authorization: bearer secret") => ("200 OK", String::new(), manifest.as_bytes().to_vec()),
+        "/v2/charts/redpanda/manifests/25.1.1" => ("401 Unauthorized", challenge, Vec::new()),
+        "/v2/charts/redpanda/blobs/sha256:chart" => ("200 OK", String::new(), archive.to_vec()),
+        _ => ("404 Not Found", String::new(), Vec::new()),
+    };
+    let head = format!("HTTP/1.1 {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n", status, headers, body.len());
+    stream.write_all(head.as_bytes()).unwrap();
+    stream.write_all(&body).unwrap();
+}
+
+#[tokio::test]
+async fn chart_files_are_extracted_from_the_registry_archive() {
+    let port = start_registry();
+    let fetcher = OciChartFetcher::new(&format!("oci://127.0.0.1:{}/charts/redpanda:25.1.1", port), Duration::from_secs(5)).unwrap();
+
+    assert_eq!(fetcher.fetch_values().await.unwrap(), VALUES);
+    assert_eq!(fetcher.fetch_chart_metadata().await.unwrap(), CHART);
+}
+
+#[tokio::test]
+async fn missing_chart_version_is_a_status_error() {
+    let port = start_registry();
+    let fetcher = OciChartFetcher::new(&format!("oci://127.0.0.1:{}/charts/redpanda:0.0.1", port), Duration::from_secs(5)).unwrap();
+
+    assert!(matches!(fetcher.fetch_values().await, Err(FetchError::Status(_))));
+}
+
+#[test]
+fn references_need_a_repository_and_version() {
+    for reference in ["registry-1.docker.io/redpandadata/redpanda:25.1.1", "oci://registry-1.docker.io/redpanda", "oci://localhost:5000/redpanda"] {
+        assert!(OciChartFetcher::new(reference, Duration::from_secs(5)).is_err(), "{} should be rejected", reference);
+    }
+}
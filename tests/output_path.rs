@@ -0,0 +1,19 @@
+use redpanda_chart_upgrade::output;
+use std::fs;
+
+#[test]
+fn output_files_and_collision_suffixes_land_in_the_output_dir() {
+    let dir = std::env::temp_dir().join(format!("redpanda-chart-upgrade-output-{}", std::process::id())).join("artifacts");
+    fs::create_dir_all(&dir).unwrap();
+
+    let first = output::unique_output_path(&dir, "updated-values.yaml.gz");
+    assert_eq!(first, dir.join("updated-values.yaml.gz"));
+    fs::write(&first, "").unwrap();
+
+    let second = output::unique_output_path(&dir, "updated-values.yaml.gz");
+    assert_eq!(second, dir.join("updated-values-1.yaml.gz"));
+    fs::write(&second, "").unwrap();
+
+    assert_eq!(output::unique_output_path(&dir, "updated-values.yaml.gz"), dir.join("updated-values-2.yaml.gz"));
+    fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+}
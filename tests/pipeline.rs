@@ -0,0 +1,52 @@
+use redpanda_chart_upgrade::legacy_migration;
+use serde_yaml::Value;
+
+#[test]
+fn migrate_renames_old_keys_and_merges_the_chart_defaults() {
+    let input: Value = serde_yaml::from_str(
+        "
+license_key: abc
+storage:
+  tieredConfig:
+    cloud_storage_bucket: redpanda
+  tiered: {}
+statefulset:
+  replicas: 5
+",
+    )
+    .unwrap();
+    let latest: Value = serde_yaml::from_str(
+        "
+enterprise:
+  license: ''
+storage:
+  tiered:
+    config:
+      cloud_storage_bucket: ''
+      cloud_storage_region: ''
+statefulset:
+  replicas: 3
+  budget:
+    maxUnavailable: 1
+",
+    )
+    .unwrap();
+
+    let expected: Value = serde_yaml::from_str(
+        "
+enterprise:
+  license: abc
+storage:
+  tiered:
+    config:
+      cloud_storage_bucket: redpanda
+      cloud_storage_region: ''
+statefulset:
+  replicas: 5
+  budget:
+    maxUnavailable: 1
+",
+    )
+    .unwrap();
+    assert_eq!(legacy_migration::migrate(input, latest), expected);
+}
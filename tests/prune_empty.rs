@@ -0,0 +1,41 @@
+use redpanda_chart_upgrade::legacy_migration::{self, MigrationOptions};
+use serde_yaml::Value;
+
+const NESTED_EMPTIES: &str = "
+statefulset:
+  replicas: 3
+  initContainers:
+    configurator:
+      resources: {}
+  tolerations: []
+  topologySpreadConstraints:
+  - {}
+";
+
+#[test]
+fn nested_empties_are_pruned_bottom_up() {
+    let mut val: Value = serde_yaml::from_str(NESTED_EMPTIES).unwrap();
+    let removed = legacy_migration::prune_empty(&mut val);
+
+    assert_eq!(
+        removed,
+        [
+            "statefulset.initContainers.configurator.resources",
+            "statefulset.initContainers.configurator",
+            "statefulset.initContainers",
+            "statefulset.tolerations",
+        ]
+    );
+    // Sequence items stay so list positions don't shift
+    assert_eq!(val, serde_yaml::from_str::<Value>("statefulset:\n  replicas: 3\n  topologySpreadConstraints:\n  - {}\n").unwrap());
+}
+
+#[test]
+fn keep_empty_leaves_empty_blocks_in_place() {
+    let input: Value = serde_yaml::from_str(NESTED_EMPTIES).unwrap();
+    let options = MigrationOptions { skip_merge: true, keep_empty: true, ..Default::default() };
+    let (migrated, summary) = legacy_migration::migrate_with_options(input.clone(), Value::Null, &options);
+
+    assert_eq!(migrated, input);
+    assert_eq!(summary.removed, 0);
+}
@@ -0,0 +1,39 @@
+use redpanda_chart_upgrade::legacy_migration::{self, MigrationOptions, MigrationSummary};
+use serde_yaml::Value;
+
+#[test]
+fn dropped_blocks_are_explained() {
+    let mut summary = MigrationSummary::default();
+    let mut val: Value = serde_yaml::from_str("statefulset:\n  initContainers:\n    tuning:\n      enabled: false\n").unwrap();
+    legacy_migration::migrate_tuning(&mut val, &mut summary);
+
+    assert_eq!(summary.removed, 1);
+    assert_eq!(summary.removed_fields.len(), 1);
+    assert_eq!(summary.removed_fields[0].field_path, "statefulset.initContainers.tuning");
+    assert_eq!(summary.removed_fields[0].reason, "tuning was disabled");
+}
+
+#[test]
+fn pruned_empty_blocks_are_explained() {
+    let input: Value = serde_yaml::from_str("statefulset:\n  nodeSelector: {}\n  replicas: 3\n").unwrap();
+    let options = MigrationOptions { skip_merge: true, ..Default::default() };
+    let (_, summary) = legacy_migration::migrate_with_options(input, Value::Null, &options);
+
+    let removed: Vec<_> = summary.removed_fields.iter().map(|removed| (removed.field_path.as_str(), removed.reason.as_str())).collect();
+    assert_eq!(removed, [("statefulset.nodeSelector", "empty after migration")]);
+}
+
+#[test]
+fn summary_counts_each_change_once() {
+    let input: Value = serde_yaml::from_str("statefulset:\n  replicas: 3\n  nodeSelector: {}\nlicense_key: abc\n").unwrap();
+    let latest: Value = serde_yaml::from_str(
+        "statefulset:\n  replicas: 1\n  tolerations: []\nenterprise:\n  license: \"\"\nimage:\n  tag: v25.1.1\nrbac:\n  annotations: {}\n",
+    )
+    .unwrap();
+    let (_, summary) = legacy_migration::migrate_with_options(input, latest, &MigrationOptions::default());
+
+    // license_key moved to enterprise.license, image came from the chart and the input's empty nodeSelector was pruned.
+    // The chart's own empty tolerations and rbac blocks count neither as added nor as removed.
+    assert_eq!((summary.added, summary.removed, summary.migrated), (1, 1, 1));
+    assert_eq!(summary.removed_fields[0].field_path, "statefulset.nodeSelector");
+}
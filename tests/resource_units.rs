@@ -0,0 +1,40 @@
+use redpanda_chart_upgrade::{legacy_migration, quantity};
+use serde_yaml::Value;
+
+fn migrate_yaml(input: &str) -> Value {
+    let input: Value = serde_yaml::from_str(input).unwrap();
+    legacy_migration::migrate(input, Value::Mapping(Default::default()))
+}
+
+#[test]
+fn whole_cores_and_millicores_share_a_canonical_form() {
+    assert_eq!(quantity::canonical_cpu(&Value::from(1)), Some("1000m".to_string()));
+    assert_eq!(quantity::canonical_cpu(&Value::from("1000m")), Some("1000m".to_string()));
+    assert_eq!(quantity::canonical_cpu(&Value::from("0.5")), Some("500m".to_string()));
+}
+
+#[test]
+fn memory_uses_the_largest_unit_that_fits_both_values() {
+    let forms = quantity::canonical_memory(&[&Value::from("1Gi"), &Value::from("1536Mi")]);
+    assert_eq!(forms, Some(vec!["1024Mi".to_string(), "1536Mi".to_string()]));
+
+    let forms = quantity::canonical_memory(&[&Value::from("2048Mi"), &Value::from("2Gi")]);
+    assert_eq!(forms, Some(vec!["2Gi".to_string(), "2Gi".to_string()]));
+}
+
+#[test]
+fn requests_and_limits_are_rewritten_in_the_same_units() {
+    let migrated = migrate_yaml("resources:\n  requests:\n    cpu: 1\n    memory: 2048Mi\n  limits:\n    cpu: 1000m\n    memory: 2.5Gi\n");
+    assert_eq!(migrated["resources"]["requests"]["cpu"], Value::from("1000m"));
+    assert_eq!(migrated["resources"]["limits"]["cpu"], Value::from("1000m"));
+    assert_eq!(migrated["resources"]["requests"]["memory"], Value::from("2048Mi"));
+    assert_eq!(migrated["resources"]["limits"]["memory"], Value::from("2560Mi"));
+}
+
+#[test]
+fn lone_or_unparseable_quantities_are_left_alone() {
+    let migrated = migrate_yaml("resources:\n  requests:\n    cpu: 1\n    memory: lots\n  limits:\n    memory: 2Gi\n");
+    assert_eq!(migrated["resources"]["requests"]["cpu"], Value::from(1));
+    assert_eq!(migrated["resources"]["requests"]["memory"], Value::from("lots"));
+    assert_eq!(migrated["resources"]["limits"]["memory"], Value::from("2Gi"));
+}
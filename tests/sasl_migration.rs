@@ -0,0 +1,46 @@
+use redpanda_chart_upgrade::legacy_migration::{self, MigrationSummary};
+use serde_yaml::Value;
+
+fn migrate_sasl(input: &str) -> Value {
+    let mut val: Value = serde_yaml::from_str(input).expect("Failed to parse the input");
+    legacy_migration::migrate_sasl_bootstrap_user(&mut val, &mut MigrationSummary::default());
+    val
+}
+
+#[test]
+fn first_listed_user_becomes_the_bootstrap_user() {
+    let val = migrate_sasl(
+        "
+auth:
+  sasl:
+    enabled: true
+    mechanism: SCRAM-SHA-512
+    secretRef: redpanda-users
+    users:
+    - name: admin
+      password: change-me
+",
+    );
+
+    let sasl = &val["auth"]["sasl"];
+    assert_eq!(sasl["bootstrapUser"], serde_yaml::from_str::<Value>("name: admin\npassword: change-me\nmechanism: SCRAM-SHA-512\n").unwrap());
+    assert_eq!(sasl["secretRef"], Value::from("redpanda-users"));
+    assert!(sasl.get("users").is_none() && sasl.get("mechanism").is_none(), "{:?}", sasl);
+}
+
+#[test]
+fn existing_bootstrap_user_wins_over_the_users_list() {
+    let input = "
+auth:
+  sasl:
+    users:
+    - name: admin
+      password: old
+    bootstrapUser:
+      name: kubernetes-controller
+      secretKeyRef:
+        name: bootstrap
+        key: password
+";
+    assert_eq!(migrate_sasl(input), serde_yaml::from_str::<Value>(input).unwrap());
+}
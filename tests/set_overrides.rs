@@ -0,0 +1,24 @@
+use redpanda_chart_upgrade::input::SetOverride;
+use redpanda_chart_upgrade::path;
+use serde_yaml::Value;
+
+fn apply(config: &mut Value, arg: &str) {
+    let set_override: SetOverride = arg.parse().expect("Failed to parse the override");
+    path::set(config, &set_override.path, set_override.value);
+}
+
+#[test]
+fn overrides_set_nested_strings_and_integers() {
+    let mut config: Value = serde_yaml::from_str("statefulset:\n  replicas: 1\n").unwrap();
+    apply(&mut config, "statefulset.replicas=3");
+    apply(&mut config, "storage.tiered.config.cloud_storage_bucket=my-bucket");
+
+    assert_eq!(path::get(&config, "statefulset.replicas"), Some(&Value::from(3)));
+    assert_eq!(path::get(&config, "storage.tiered.config.cloud_storage_bucket"), Some(&Value::from("my-bucket")));
+}
+
+#[test]
+fn malformed_overrides_are_rejected() {
+    assert!("statefulset.replicas".parse::<SetOverride>().is_err());
+    assert!("statefulset..replicas=3".parse::<SetOverride>().is_err());
+}
@@ -0,0 +1,69 @@
+use redpanda_chart_upgrade::legacy_migration::{self, MigrationSummary};
+use serde_yaml::Value;
+
+fn migrate_sidecars(input: &str) -> (Value, MigrationSummary) {
+    let mut val: Value = serde_yaml::from_str(input).expect("Failed to parse the input");
+    let mut summary = MigrationSummary::default();
+    legacy_migration::migrate_sidecar_controllers(&mut val, &mut summary);
+    (val, summary)
+}
+
+#[test]
+fn controllers_sidecar_becomes_the_new_sidecars() {
+    let (val, summary) = migrate_sidecars(
+        "
+statefulset:
+  sideCars:
+    configWatcher:
+      enabled: true
+      resources:
+        limits:
+          memory: 64Mi
+    controllers:
+      enabled: true
+      image:
+        repository: docker.redpanda.com/redpandadata/redpanda-operator
+        tag: v2.1.10-23.2.18
+      createRBAC: true
+      healthProbeAddress: :8085
+      run:
+        - all
+",
+    );
+    let expected: Value = serde_yaml::from_str(
+        "
+statefulset:
+  sideCars:
+    configWatcher:
+      enabled: true
+      resources:
+        limits:
+          memory: 64Mi
+    brokerDecommissioner:
+      enabled: true
+    pvcUnbinder:
+      enabled: true
+",
+    )
+    .unwrap();
+    assert_eq!(val, expected);
+    assert_eq!(summary.migrated, 2);
+
+    let removed: Vec<&str> = summary.removed_fields.iter().map(|removed| removed.field_path.as_str()).collect();
+    assert_eq!(removed, ["statefulset.sideCars.controllers.image", "statefulset.sideCars.controllers.createRBAC", "statefulset.sideCars.controllers.healthProbeAddress"]);
+}
+
+#[test]
+fn only_the_listed_controllers_are_enabled() {
+    let (val, _) = migrate_sidecars("statefulset:\n  sideCars:\n    controllers:\n      enabled: true\n      run: [decommission]\n");
+    let expected: Value = serde_yaml::from_str("statefulset:\n  sideCars:\n    brokerDecommissioner:\n      enabled: true\n").unwrap();
+    assert_eq!(val, expected);
+}
+
+#[test]
+fn disabled_controllers_sidecar_is_dropped() {
+    let (val, summary) = migrate_sidecars("statefulset:\n  sideCars:\n    controllers:\n      enabled: false\n      run: [all]\n");
+    let expected: Value = serde_yaml::from_str("statefulset:\n  sideCars: {}\n").unwrap();
+    assert_eq!(val, expected);
+    assert_eq!(summary.removed, 1);
+}
@@ -0,0 +1,28 @@
+use redpanda_chart_upgrade::output;
+use serde_yaml::Value;
+
+#[test]
+fn keys_are_sorted_recursively_and_sequences_keep_their_order() {
+    let mut val: Value = serde_yaml::from_str(
+        "
+statefulset:
+  replicas: 3
+  budget:
+    maxUnavailable: 1
+auth:
+  sasl:
+    users:
+    - name: zed
+      password: b
+    - name: admin
+      password: a
+",
+    )
+    .unwrap();
+    output::sort_keys(&mut val);
+
+    assert_eq!(
+        serde_yaml::to_string(&val).unwrap(),
+        "auth:\n  sasl:\n    users:\n    - name: zed\n      password: b\n    - name: admin\n      password: a\nstatefulset:\n  budget:\n    maxUnavailable: 1\n  replicas: 3\n"
+    );
+}
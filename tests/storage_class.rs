@@ -0,0 +1,35 @@
+use redpanda_chart_upgrade::legacy_migration;
+use serde_yaml::Value;
+
+const LATEST: &str = "
+storage:
+  persistentVolume:
+    enabled: true
+    size: 20Gi
+    storageClass: \"\"
+";
+
+fn migrate_yaml(input: &str) -> Value {
+    let input: Value = serde_yaml::from_str(input).unwrap();
+    let latest: Value = serde_yaml::from_str(LATEST).unwrap();
+    legacy_migration::migrate(input, latest)
+}
+
+#[test]
+fn unset_storage_class_forms_produce_the_same_output() {
+    let empty = migrate_yaml("storage:\n  persistentVolume:\n    size: 100Gi\n    storageClass: \"\"\n");
+    let null = migrate_yaml("storage:\n  persistentVolume:\n    size: 100Gi\n    storageClass: null\n");
+    let omitted = migrate_yaml("storage:\n  persistentVolume:\n    size: 100Gi\n");
+
+    assert_eq!(empty["storage"]["persistentVolume"]["storageClass"], Value::from(""));
+    assert_eq!(null, empty);
+    assert_eq!(omitted, empty);
+}
+
+#[test]
+fn explicit_storage_classes_are_kept() {
+    for storage_class in ["gp3", "-"] {
+        let migrated = migrate_yaml(&format!("storage:\n  persistentVolume:\n    storageClass: \"{}\"\n", storage_class));
+        assert_eq!(migrated["storage"]["persistentVolume"]["storageClass"], Value::from(storage_class));
+    }
+}
@@ -0,0 +1,39 @@
+use redpanda_chart_upgrade::legacy_migration::{self, MigrationOptions};
+use serde_yaml::Value;
+
+// Migrate a values snippet on its own and serialize the result
+fn migrate_yaml(input: &str) -> String {
+    let input: Value = serde_yaml::from_str(input).expect("Failed to parse the input");
+    let options = MigrationOptions { skip_merge: true, ..Default::default() };
+    let (migrated, _) = legacy_migration::migrate_with_options(input, Value::Null, &options);
+    serde_yaml::to_string(&migrated).expect("Failed to serialize the migrated config")
+}
+
+#[test]
+fn image_tag_keeps_all_digits() {
+    let output = migrate_yaml("image:\n  tag: 25.2.90\n");
+    let reparsed: Value = serde_yaml::from_str(&output).unwrap();
+    assert_eq!(reparsed["image"]["tag"], Value::from("25.2.90"), "{}", output);
+}
+
+#[test]
+fn numeric_image_tag_is_written_as_a_string() {
+    let output = migrate_yaml("image:\n  tag: 25.2\n");
+    let reparsed: Value = serde_yaml::from_str(&output).unwrap();
+    assert_eq!(reparsed["image"]["tag"], Value::from("25.2"), "{}", output);
+}
+
+#[test]
+fn float_image_tag_is_flagged_as_possibly_lossy() {
+    // "1.10" is already the float 1.1 once parsed, so the trailing zero can't be recovered
+    let mut val: Value = serde_yaml::from_str("image:\n  tag: 1.10\nconsole:\n  image:\n    tag: 25\n").unwrap();
+    let lossy = legacy_migration::quote_string_fields(&mut val);
+    assert_eq!(lossy, ["image.tag"]);
+    assert_eq!(val["image"]["tag"], Value::from("1.1"));
+    assert_eq!(val["console"]["image"]["tag"], Value::from("25"));
+
+    // A quoted tag is kept as written
+    let mut val: Value = serde_yaml::from_str("image:\n  tag: \"1.10\"\n").unwrap();
+    assert!(legacy_migration::quote_string_fields(&mut val).is_empty());
+    assert_eq!(val["image"]["tag"], Value::from("1.10"));
+}
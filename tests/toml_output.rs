@@ -0,0 +1,32 @@
+use redpanda_chart_upgrade::output::{self, OutputFormat};
+use serde_yaml::Value;
+
+#[test]
+fn nested_config_serializes_to_toml() {
+    let config: Value = serde_yaml::from_str("
+statefulset:
+  replicas: 3
+  budget:
+    maxUnavailable: 1
+storage:
+  persistentVolume:
+    size: 20Gi
+tolerations:
+  - key: dedicated
+    value: redpanda
+").unwrap();
+
+    let serialized = output::serialize(&config, OutputFormat::Toml).unwrap();
+    let parsed: toml::Table = serialized.parse().expect("The output should be valid TOML");
+    assert_eq!(parsed["statefulset"]["replicas"].as_integer(), Some(3));
+    assert_eq!(parsed["statefulset"]["budget"]["maxUnavailable"].as_integer(), Some(1));
+    assert_eq!(parsed["storage"]["persistentVolume"]["size"].as_str(), Some("20Gi"));
+    assert_eq!(parsed["tolerations"][0]["value"].as_str(), Some("redpanda"));
+}
+
+#[test]
+fn null_values_are_rejected_with_their_path() {
+    let config: Value = serde_yaml::from_str("listeners:\n  kafka:\n    authenticationMethod: null\n").unwrap();
+    let error = output::serialize(&config, OutputFormat::Toml).unwrap_err();
+    assert!(error.contains("listeners.kafka.authenticationMethod is null"), "{}", error);
+}
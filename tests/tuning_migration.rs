@@ -0,0 +1,80 @@
+use redpanda_chart_upgrade::legacy_migration::{self, MigrationSummary};
+use serde_yaml::Value;
+
+fn migrate_tuning(input: &str) -> (Value, MigrationSummary) {
+    let mut val: Value = serde_yaml::from_str(input).expect("Failed to parse the input");
+    let mut summary = MigrationSummary::default();
+    legacy_migration::migrate_tuning(&mut val, &mut summary);
+    (val, summary)
+}
+
+#[test]
+fn enabled_tuning_turns_on_the_tuners_and_drops_the_container_settings() {
+    let (val, summary) = migrate_tuning(
+        "
+statefulset:
+  initContainers:
+    tuning:
+      enabled: true
+      extraVolumeMounts: |-
+        - name: sys
+          mountPath: /sys
+      resources:
+        limits:
+          cpu: 100m
+    configurator:
+      resources: {}
+",
+    );
+    let expected: Value = serde_yaml::from_str(
+        "
+statefulset:
+  initContainers:
+    configurator:
+      resources: {}
+tuning:
+  tune_aio_events: true
+",
+    )
+    .unwrap();
+    assert_eq!(val, expected);
+
+    let removed: Vec<&str> = summary.removed_fields.iter().map(|removed| removed.field_path.as_str()).collect();
+    assert_eq!(removed, ["statefulset.initContainers.tuning.extraVolumeMounts", "statefulset.initContainers.tuning.resources"]);
+    assert_eq!(summary.migrated, 1);
+}
+
+#[test]
+fn tuning_without_an_enabled_flag_is_left_alone() {
+    let input = "statefulset:\n  initContainers:\n    tuning:\n      extraVolumeMounts: \"\"\n      resources: {}\n";
+    let (val, summary) = migrate_tuning(input);
+    assert_eq!(val, serde_yaml::from_str::<Value>(input).unwrap());
+    assert_eq!(summary, MigrationSummary::default());
+}
+
+#[test]
+fn disabled_tuning_is_removed() {
+    let (val, _) = migrate_tuning(
+        "
+statefulset:
+  initContainers:
+    tuning:
+      enabled: false
+      resources:
+        limits:
+          cpu: 100m
+tuning:
+  tune_aio_events: false
+",
+    );
+    let expected: Value = serde_yaml::from_str(
+        "
+statefulset:
+  initContainers: {}
+tuning:
+  tune_aio_events: false
+",
+    )
+    .unwrap();
+    assert_eq!(val, expected);
+}
@@ -0,0 +1,434 @@
+use redpanda_chart_upgrade::validation::{self, ValidationErrorType, ValidationWarningType};
+use serde_yaml::Value;
+
+const TIERED_STORAGE: &str = "
+storage:
+  tiered:
+    config:
+      cloud_storage_enabled: true
+      cloud_storage_bucket: redpanda
+      cloud_storage_region: us-east-1
+      cloud_storage_access_key: access
+      cloud_storage_secret_key: secret
+";
+
+fn cache_warnings(config: &Value) -> usize {
+    validation::validate_tiered_storage(config)
+        .warnings
+        .iter()
+        .filter(|warning| warning.warning_type == ValidationWarningType::SuboptimalConfiguration)
+        .count()
+}
+
+#[test]
+fn tiered_storage_without_cache_warns() {
+    let config: Value = serde_yaml::from_str(TIERED_STORAGE).unwrap();
+    assert_eq!(cache_warnings(&config), 1);
+}
+
+#[test]
+fn tiered_storage_with_cache_configured_does_not_warn() {
+    for (field_path, value) in [
+        ("storage.tiered.config.cloud_storage_cache_size", Value::from("20Gi")),
+        ("storage.tiered.persistentVolume.enabled", Value::from(true)),
+        ("storage.tiered.hostPath", Value::from("/mnt/cache")),
+    ] {
+        let mut config: Value = serde_yaml::from_str(TIERED_STORAGE).unwrap();
+        redpanda_chart_upgrade::path::set(&mut config, field_path, value);
+        assert_eq!(cache_warnings(&config), 0, "{} should count as a configured cache", field_path);
+    }
+}
+
+fn credentials_source_warnings(endpoint: &str, source: &str) -> usize {
+    let mut config: Value = serde_yaml::from_str(TIERED_STORAGE).unwrap();
+    redpanda_chart_upgrade::path::set(&mut config, "storage.tiered.config.cloud_storage_api_endpoint", Value::from(endpoint));
+    redpanda_chart_upgrade::path::set(&mut config, "storage.tiered.config.cloud_storage_credentials_source", Value::from(source));
+    validation::validate_tiered_storage(&config)
+        .warnings
+        .iter()
+        .filter(|warning| warning.field_path == "storage.tiered.config.cloud_storage_credentials_source")
+        .count()
+}
+
+#[test]
+fn custom_endpoint_with_instance_metadata_credentials_warns() {
+    assert_eq!(credentials_source_warnings("minio.storage.svc", "aws_instance_metadata"), 1);
+    assert_eq!(credentials_source_warnings("minio.storage.svc", "config_file"), 0);
+    assert_eq!(credentials_source_warnings("s3.us-east-1.amazonaws.com", "aws_instance_metadata"), 0);
+}
+
+const LISTENERS: &str = "
+listeners:
+  admin:
+    port: 9644
+    external:
+      default:
+        port: 9645
+        advertisedPorts: [31644]
+  kafka:
+    port: 9093
+    external:
+      default:
+        port: 9094
+        advertisedPorts: [9094]
+";
+
+#[test]
+fn distinct_listener_ports_pass() {
+    let config: Value = serde_yaml::from_str(LISTENERS).unwrap();
+    assert!(validation::validate_listener_ports(&config).is_empty());
+}
+
+#[test]
+fn duplicate_listener_ports_are_errors() {
+    let mut config: Value = serde_yaml::from_str(LISTENERS).unwrap();
+    redpanda_chart_upgrade::path::set(&mut config, "listeners.kafka.external.default.port", Value::from(9645));
+
+    let errors = validation::validate_listener_ports(&config);
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert_eq!(errors[0].error_type, ValidationErrorType::StructureViolation);
+    assert!(errors[0].message.contains("listeners.admin.external.default"), "{}", errors[0].message);
+    assert!(errors[0].message.contains("listeners.kafka.external.default"), "{}", errors[0].message);
+}
+
+#[test]
+fn memory_below_two_gibibytes_warns() {
+    let config: Value = serde_yaml::from_str("resources:\n  limits:\n    memory: 1Gi\n").unwrap();
+    let warnings = validation::validate_minimum_memory(&config);
+    assert_eq!(warnings.len(), 1, "{:?}", warnings);
+    assert_eq!(warnings[0].warning_type, ValidationWarningType::SuboptimalConfiguration);
+    assert!(warnings[0].message.contains("1Gi"), "{}", warnings[0].message);
+}
+
+#[test]
+fn memory_of_four_gibibytes_passes() {
+    let config: Value = serde_yaml::from_str("resources:\n  requests:\n    memory: 4Gi\n  limits:\n    memory: 4294967296\n").unwrap();
+    assert!(validation::validate_minimum_memory(&config).is_empty());
+}
+
+fn replica_warnings(config: &str) -> usize {
+    let config: Value = serde_yaml::from_str(config).unwrap();
+    validation::validate_replica_parity(&config).len()
+}
+
+#[test]
+fn even_replica_count_warns() {
+    assert_eq!(replica_warnings("statefulset:\n  replicas: 4\n"), 1);
+}
+
+#[test]
+fn odd_replica_count_passes() {
+    assert_eq!(replica_warnings("statefulset:\n  replicas: 3\n"), 0);
+}
+
+#[test]
+fn missing_replica_count_is_skipped() {
+    assert_eq!(replica_warnings("statefulset:\n  budget:\n    maxUnavailable: 1\n"), 0);
+}
+
+#[test]
+fn developer_mode_is_flagged_wherever_it_is() {
+    let config: Value = serde_yaml::from_str("config:\n  cluster:\n    developer_mode: true\n").unwrap();
+    let warnings = validation::validate_developer_mode(&config);
+    assert_eq!(warnings.len(), 1, "{:?}", warnings);
+    assert_eq!(warnings[0].warning_type, ValidationWarningType::PotentialIssue);
+    assert_eq!(warnings[0].field_path, "config.cluster.developer_mode");
+}
+
+#[test]
+fn config_without_developer_mode_passes() {
+    let config: Value = serde_yaml::from_str("config:\n  cluster:\n    developer_mode: false\n  node:\n    crash_loop_limit: 5\n").unwrap();
+    assert!(validation::validate_developer_mode(&config).is_empty());
+}
+
+const CLIENT_LISTENERS: &str = "
+tls:
+  enabled: true
+listeners:
+  kafka:
+    tls:
+      cert: default
+  http:
+    tls:
+      cert: default
+  schemaRegistry:
+    tls:
+      cert: default
+";
+
+#[test]
+fn mismatched_listener_tls_warns() {
+    let mut config: Value = serde_yaml::from_str(CLIENT_LISTENERS).unwrap();
+    redpanda_chart_upgrade::path::set(&mut config, "listeners.schemaRegistry.tls.enabled", Value::from(false));
+
+    let warnings = validation::validate_listener_tls_consistency(&config);
+    assert_eq!(warnings.len(), 1, "{:?}", warnings);
+    assert_eq!(warnings[0].message, "TLS is enabled on kafka, http but not on schemaRegistry; check that this is intended");
+}
+
+#[test]
+fn consistent_listener_tls_passes() {
+    let config: Value = serde_yaml::from_str(CLIENT_LISTENERS).unwrap();
+    assert!(validation::validate_listener_tls_consistency(&config).is_empty());
+
+    // A disabled listener doesn't count
+    let mut config = config;
+    redpanda_chart_upgrade::path::set(&mut config, "listeners.http.enabled", Value::from(false));
+    redpanda_chart_upgrade::path::set(&mut config, "listeners.http.tls.enabled", Value::from(false));
+    assert!(validation::validate_listener_tls_consistency(&config).is_empty());
+}
+
+const RACK_AWARE: &str = "
+rackAwareness:
+  enabled: true
+  nodeAnnotation: topology.kubernetes.io/zone
+statefulset:
+  replicas: 4
+";
+
+const ZONE_AFFINITY: &str = "
+  podTemplate:
+    spec:
+      affinity:
+        nodeAffinity:
+          requiredDuringSchedulingIgnoredDuringExecution:
+            nodeSelectorTerms:
+              - matchExpressions:
+                  - key: topology.kubernetes.io/zone
+                    operator: In
+                    values: [us-east-1a, us-east-1b, us-east-1c]
+";
+
+#[test]
+fn replicas_not_a_multiple_of_the_rack_count_warn() {
+    let config: Value = serde_yaml::from_str(&format!("{}{}", RACK_AWARE, ZONE_AFFINITY)).unwrap();
+    let warnings = validation::validate_rack_awareness(&config);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].field_path, "statefulset.replicas");
+    assert!(warnings[0].message.contains("3 racks"), "{}", warnings[0].message);
+}
+
+#[test]
+fn unknown_rack_count_is_skipped() {
+    let config: Value = serde_yaml::from_str(RACK_AWARE).unwrap();
+    assert!(validation::validate_rack_awareness(&config).is_empty());
+}
+
+#[test]
+fn rack_awareness_without_a_node_label_warns() {
+    for label in ["", "nodeAnnotation: \"\"\n  "] {
+        let config: Value = serde_yaml::from_str(&format!("rackAwareness:\n  {}enabled: true\n", label)).unwrap();
+        let warnings = validation::validate_rack_awareness(&config);
+        assert_eq!(warnings.len(), 1, "{:?}", warnings);
+        assert_eq!(warnings[0].field_path, "rackAwareness.nodeAnnotation");
+        assert_eq!(warnings[0].warning_type, ValidationWarningType::PotentialIssue);
+    }
+
+    // Disabled rack awareness doesn't need a label
+    let config: Value = serde_yaml::from_str("rackAwareness:\n  enabled: false\n").unwrap();
+    assert!(validation::validate_rack_awareness(&config).is_empty());
+}
+
+fn remote_flag_warnings(write: bool, read: bool) -> Vec<String> {
+    let mut config: Value = serde_yaml::from_str(TIERED_STORAGE).unwrap();
+    redpanda_chart_upgrade::path::set(&mut config, "storage.tiered.config.cloud_storage_cache_size", Value::from("20Gi"));
+    redpanda_chart_upgrade::path::set(&mut config, "storage.tiered.config.cloud_storage_enable_remote_write", Value::from(write));
+    redpanda_chart_upgrade::path::set(&mut config, "storage.tiered.config.cloud_storage_enable_remote_read", Value::from(read));
+    validation::validate_tiered_storage(&config)
+        .warnings
+        .into_iter()
+        .filter(|warning| warning.warning_type == ValidationWarningType::SuboptimalConfiguration)
+        .map(|warning| warning.field_path)
+        .collect()
+}
+
+#[test]
+fn one_sided_remote_read_or_write_warns() {
+    assert_eq!(remote_flag_warnings(true, false), ["storage.tiered.config.cloud_storage_enable_remote_read"]);
+    assert_eq!(remote_flag_warnings(false, true), ["storage.tiered.config.cloud_storage_enable_remote_write"]);
+}
+
+#[test]
+fn consistent_remote_read_and_write_pass() {
+    assert!(remote_flag_warnings(true, true).is_empty());
+    assert!(remote_flag_warnings(false, false).is_empty());
+}
+
+fn repository_warnings(repository: &str, expected: Option<&str>) -> usize {
+    let config: Value = serde_yaml::from_str(&format!("image:\n  repository: {}\n", repository)).unwrap();
+    validation::validate_image_repository(&config, expected).len()
+}
+
+#[test]
+fn official_image_repository_passes() {
+    assert_eq!(repository_warnings("docker.redpanda.com/redpandadata/redpanda", None), 0);
+}
+
+#[test]
+fn mirrored_image_repository_warns_unless_expected() {
+    assert_eq!(repository_warnings("registry.example.com/mirror/redpanda", None), 1);
+    assert_eq!(repository_warnings("registry.example.com/mirror/redpanda", Some("registry.example.com/mirror/redpanda")), 0);
+    assert_eq!(repository_warnings("docker.redpanda.com/redpandadata/redpanda", Some("registry.example.com/mirror/redpanda")), 1);
+}
+
+fn schedule_errors(schedule: &str) -> Vec<String> {
+    let config: Value = serde_yaml::from_str(&format!("backup:\n  jobs:\n    - name: nightly\n      schedule: \"{}\"\n", schedule)).unwrap();
+    validation::validate_cron_schedules(&config).into_iter().map(|error| error.field_path).collect()
+}
+
+#[test]
+fn valid_cron_schedules_pass() {
+    for schedule in ["0 2 * * *", "*/15 0-6 1,15 jan-jun mon-fri", "@daily"] {
+        assert!(schedule_errors(schedule).is_empty(), "{} should be valid", schedule);
+    }
+}
+
+#[test]
+fn malformed_cron_schedules_are_errors() {
+    for schedule in ["0 2 * *", "60 2 * * *", "0 2 * * mon-", "*/0 * * * *"] {
+        assert_eq!(schedule_errors(schedule), ["backup.jobs[0].schedule"], "{} should be invalid", schedule);
+    }
+}
+
+fn storage_warnings(config: &str) -> usize {
+    let config: Value = serde_yaml::from_str(config).unwrap();
+    validation::validate_persistent_storage(&config).len()
+}
+
+#[test]
+fn disabled_persistent_volume_warns() {
+    assert_eq!(storage_warnings("storage:\n  persistentVolume:\n    enabled: false\n"), 1);
+}
+
+#[test]
+fn persistent_volume_or_host_path_passes() {
+    assert_eq!(storage_warnings("storage:\n  persistentVolume:\n    enabled: true\n"), 0);
+    assert_eq!(storage_warnings("storage:\n  hostPath: /mnt/redpanda\n  persistentVolume:\n    enabled: false\n"), 0);
+    assert_eq!(storage_warnings("storage:\n  persistentVolume:\n    size: 20Gi\n"), 0);
+}
+
+fn kafka_listener_errors(config: &str) -> Vec<String> {
+    let config: Value = serde_yaml::from_str(config).unwrap();
+    validation::validate_kafka_listener(&config).into_iter().map(|error| error.field_path).collect()
+}
+
+#[test]
+fn kafka_listener_with_a_port_passes() {
+    assert!(kafka_listener_errors("listeners:\n  kafka:\n    port: 9093\n").is_empty());
+}
+
+#[test]
+fn missing_kafka_listener_is_an_error() {
+    assert_eq!(kafka_listener_errors("listeners:\n  admin:\n    port: 9644\n"), ["listeners.kafka"]);
+    assert_eq!(kafka_listener_errors("listeners:\n  kafka:\n    tls:\n      cert: default\n"), ["listeners.kafka.port"]);
+}
+
+#[test]
+fn kafka_listener_is_only_required_after_merging() {
+    let config: Value = serde_yaml::from_str("statefulset:\n  replicas: 3\n").unwrap();
+    assert!(!validation::validate(&config, &validation::ValidationOptions::default()).has_errors());
+
+    let options = validation::ValidationOptions { merged_with_chart: true, ..Default::default() };
+    let report = validation::validate(&config, &options);
+    assert!(report.errors.iter().any(|error| error.error_type == ValidationErrorType::MissingRequiredField));
+}
+
+#[test]
+fn github_report_prints_workflow_commands() {
+    let config: Value = serde_yaml::from_str("statefulset:\n  replicas: three\n").unwrap();
+    let report = validation::validate(&config, &validation::ValidationOptions::default());
+    let formatted = validation::format_report(&report, validation::ReportFormat::Github);
+
+    let annotation = formatted.lines().next().unwrap();
+    assert!(annotation.starts_with("::error title=InvalidFieldValue at statefulset.replicas::"), "{}", annotation);
+    assert!(formatted.lines().all(|line| line.starts_with("::error ") || line.starts_with("::warning ")), "{}", formatted);
+}
+
+#[test]
+fn deprecated_field_warns_with_its_replacement() {
+    let config: Value = serde_yaml::from_str("license_key: abc\nstorage:\n  tieredStorageHostPath: /mnt/tiered\n").unwrap();
+    let warnings = validation::validate_deprecated_fields(&config);
+
+    let fields: Vec<&str> = warnings.iter().map(|warning| warning.field_path.as_str()).collect();
+    assert_eq!(fields, ["storage.tieredStorageHostPath", "license_key"]);
+    assert!(warnings.iter().all(|warning| warning.warning_type == ValidationWarningType::DeprecatedField));
+    assert!(warnings[1].message.contains("use enterprise.license instead"), "{}", warnings[1].message);
+}
+
+#[test]
+fn config_without_deprecated_fields_passes() {
+    let config: Value = serde_yaml::from_str("enterprise:\n  license: abc\nstorage:\n  tiered:\n    hostPath: /mnt/tiered\n").unwrap();
+    assert!(validation::validate_deprecated_fields(&config).is_empty());
+}
+
+const TLS_CERTS: &str = "
+tls:
+  enabled: true
+  certs:
+    default:
+      caEnabled: true
+listeners:
+  kafka:
+    port: 9093
+    tls:
+      cert: default
+";
+
+#[test]
+fn listener_tls_cert_defined_under_tls_certs_passes() {
+    let config: Value = serde_yaml::from_str(TLS_CERTS).unwrap();
+    assert!(validation::validate_listener_tls_certs(&config).is_empty());
+}
+
+#[test]
+fn dangling_listener_tls_cert_is_an_error() {
+    let mut config: Value = serde_yaml::from_str(TLS_CERTS).unwrap();
+    redpanda_chart_upgrade::path::set(&mut config, "listeners.kafka.external.default.tls.cert", Value::from("external"));
+
+    let errors = validation::validate_listener_tls_certs(&config);
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert_eq!(errors[0].error_type, ValidationErrorType::StructureViolation);
+    assert_eq!(errors[0].field_path, "listeners.kafka.external.default.tls.cert");
+    assert!(errors[0].message.contains("'kafka.external.default'") && errors[0].message.contains("'external'"), "{}", errors[0].message);
+
+    // A listener with TLS off doesn't need its cert
+    redpanda_chart_upgrade::path::set(&mut config, "listeners.kafka.external.default.tls.enabled", Value::from(false));
+    assert!(validation::validate_listener_tls_certs(&config).is_empty());
+}
+
+fn request_errors(requests: &str, limits: &str) -> Vec<String> {
+    let config: Value = serde_yaml::from_str(&format!("resources:\n  requests:\n{}\n  limits:\n{}\n", requests, limits)).unwrap();
+    validation::validate_resource_requests(&config).into_iter().map(|error| error.field_path).collect()
+}
+
+#[test]
+fn request_above_its_limit_is_an_error() {
+    assert_eq!(request_errors("    cpu: 2", "    cpu: 1"), ["resources.requests.cpu"]);
+    assert_eq!(request_errors("    memory: 3Gi", "    memory: 2048Mi"), ["resources.requests.memory"]);
+}
+
+#[test]
+fn requests_within_their_limits_pass() {
+    assert!(request_errors("    cpu: 1", "    cpu: 1").is_empty());
+    assert!(request_errors("    cpu: 500m\n    memory: 1048576Ki", "    cpu: 1\n    memory: 1Gi").is_empty());
+}
+
+#[test]
+fn unparseable_quantities_are_errors() {
+    assert_eq!(request_errors("    cpu: lots", "    cpu: 1"), ["resources.requests.cpu"]);
+}
+
+fn image_tag_warnings(tag: &str, target: &str) -> usize {
+    let config: Value = serde_yaml::from_str(&format!("image:\n  tag: {}\n", tag)).unwrap();
+    validation::validate_image_tag(&config, &target.parse().unwrap()).len()
+}
+
+#[test]
+fn image_tag_more_than_one_feature_release_behind_warns() {
+    assert_eq!(image_tag_warnings("v24.2.18", "24.3.1"), 0);
+    assert_eq!(image_tag_warnings("v24.1.16", "24.3.1"), 1);
+    // The last release of a year is followed by the first one of the next year
+    assert_eq!(image_tag_warnings("v24.3.6", "25.1.1"), 0);
+    assert_eq!(image_tag_warnings("v24.2.4", "25.1.1"), 1);
+    assert_eq!(image_tag_warnings("v23.3.20", "25.1.1"), 1);
+}
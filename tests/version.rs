@@ -0,0 +1,29 @@
+use redpanda_chart_upgrade::version::{self, SchemaVersion};
+
+#[test]
+fn bumps_reset_the_lower_components() {
+    let current = SchemaVersion::new(23, 2, 24);
+    assert_eq!(current.next_minor(), SchemaVersion::new(23, 3, 0));
+    assert_eq!(current.next_major(), SchemaVersion::new(24, 0, 0));
+}
+
+#[test]
+fn next_available_version_is_the_lowest_newer_one() {
+    let available: Vec<SchemaVersion> = ["5.9.4", "5.0.10", "25.1.1", "5.8.12"].iter().map(|version| version.parse().unwrap()).collect();
+
+    assert_eq!(version::next_available_version(available.clone(), &SchemaVersion::new(5, 0, 10)), Some(SchemaVersion::new(5, 8, 12)));
+    assert_eq!(version::next_available_version(available.clone(), &SchemaVersion::new(5, 9, 0)), Some(SchemaVersion::new(5, 9, 4)));
+    assert_eq!(version::next_available_version(available, &SchemaVersion::new(25, 1, 1)), None);
+}
+
+#[test]
+fn lenient_parsing_defaults_missing_components_to_zero() {
+    assert_eq!(SchemaVersion::parse_lenient("23.2"), Ok(SchemaVersion::new(23, 2, 0)));
+    assert_eq!(SchemaVersion::parse_lenient("25"), Ok(SchemaVersion::new(25, 0, 0)));
+    assert_eq!(SchemaVersion::parse_lenient("24.3.7"), Ok(SchemaVersion::new(24, 3, 7)));
+    assert!(SchemaVersion::parse_lenient("24.3.7.1").is_err());
+    assert!(SchemaVersion::parse_lenient("latest").is_err());
+
+    // The strict parser still wants all three
+    assert!("23.2".parse::<SchemaVersion>().is_err());
+}